@@ -0,0 +1,72 @@
+use ark_ec::pairing::Pairing;
+use ark_ff::Field;
+use ark_std::time::Instant;
+use ark_std::{test_rng, UniformRand};
+
+use ark_yafa::yafa_108::{
+    Fq, Fq3, Fq6, Fq6Config, G1Prepared, G1Projective, G2Prepared, G2Projective, Yafa,
+};
+
+const NUM_LINES: usize = 10000;
+const NUM_MILLER_LOOPS: usize = 100;
+
+// Every Miller loop iteration multiplies the accumulator by two or more lines of the form
+// c0 + c1 * u + c4 * u * Y. A generic Fq6 multiplication costs 18 multiplications in Fq,
+// while Fq6Config::mul_by_014 costs 13.
+fn bench_line_multiplication() {
+    let mut rng = test_rng();
+    let lines = (0..NUM_LINES)
+        .map(|_| (Fq::rand(&mut rng), Fq::rand(&mut rng), Fq::rand(&mut rng)))
+        .collect::<Vec<_>>();
+    let f = Fq6::rand(&mut rng);
+
+    let timer = Instant::now();
+    let mut dense = f;
+    for (c0, c1, c4) in lines.iter() {
+        let line = Fq6::new(
+            Fq3::new(*c0, *c1, Fq::ZERO),
+            Fq3::new(Fq::ZERO, *c4, Fq::ZERO),
+        );
+        dense *= &line;
+    }
+    println!(
+        "Generic multiplication by {} lines: {}",
+        NUM_LINES,
+        timer.elapsed().as_secs_f64()
+    );
+
+    let timer = Instant::now();
+    let mut sparse = f;
+    for (c0, c1, c4) in lines.iter() {
+        Fq6Config::mul_by_014(&mut sparse, c0, c1, c4);
+    }
+    println!(
+        "Sparse multiplication by {} lines: {}",
+        NUM_LINES,
+        timer.elapsed().as_secs_f64()
+    );
+
+    assert_eq!(dense, sparse);
+}
+
+fn bench_miller_loop() {
+    let mut rng = test_rng();
+    let p = G1Prepared::from(G1Projective::rand(&mut rng));
+    let q = G2Prepared::from(G2Projective::rand(&mut rng));
+
+    let timer = Instant::now();
+    for _ in 0..NUM_MILLER_LOOPS {
+        let _ = Yafa::miller_loop(p.clone(), q.clone());
+    }
+    println!(
+        "Miller loop: {}",
+        timer.elapsed().as_secs_f64() / NUM_MILLER_LOOPS as f64
+    );
+}
+
+fn main() {
+    println!("Line multiplication on Yafa 108:");
+    bench_line_multiplication();
+    println!("Miller loop on Yafa 108:");
+    bench_miller_loop();
+}
@@ -0,0 +1,75 @@
+use ark_ff::fields::{Fp576, MontBackend, MontConfig};
+use ark_ff::{BigInteger, Field};
+use ark_std::time::Instant;
+use ark_std::{test_rng, UniformRand};
+
+use ark_yafa::{yafa_108, yafa_146};
+
+const NUM_INVERSIONS: usize = 1000;
+const NUM_PRODUCTS: usize = 100000;
+
+// The derived configurations of the base fields before they switched to safegcd, which invert
+// with the binary extended Euclidean algorithm of ark-ff.
+#[derive(MontConfig)]
+#[modulus = "34780837939964648916783391909097838466714157209549513956741661668420080527396705590597622124144427710754148825442779617551149172671304488041519255243374122027992030525080041"]
+#[generator = "11"]
+pub struct Fq108DerivedConfig;
+pub type Fq108Derived = Fp576<MontBackend<Fq108DerivedConfig, 9>>;
+
+#[derive(MontConfig)]
+#[modulus = "34780846793597649115902360759330114765953831037762036596538890219938834838634659810167270279641323601509886108333904651602464968545001642013075102043510458751533543897428201"]
+#[generator = "59"]
+pub struct Fq146DerivedConfig;
+pub type Fq146Derived = Fp576<MontBackend<Fq146DerivedConfig, 9>>;
+
+fn time<F: FnMut()>(name: &str, n: usize, mut f: F) {
+    let timer = Instant::now();
+    for _ in 0..n {
+        f();
+    }
+    println!("{}: {}", name, timer.elapsed().as_secs_f64() / n as f64);
+}
+
+// Compares the safegcd inversion with the binary extended Euclidean algorithm and with Fermat's
+// little theorem, and checks that the products of the hand-written configurations, which
+// forward to derived twins, are as fast as those of the derived configurations.
+fn bench_base_field<C: MontConfig<9>, D: MontConfig<9>>() {
+    let mut rng = test_rng();
+    let a = Fp576::<MontBackend<C, 9>>::rand(&mut rng);
+    let b = Fp576::<MontBackend<D, 9>>::new_unchecked(a.0);
+    let mut q_minus_two = C::MODULUS;
+    q_minus_two.sub_with_borrow(&2u64.into());
+
+    let mut x = a;
+    time("safegcd inverse", NUM_INVERSIONS, || {
+        x = x.inverse().unwrap()
+    });
+    let mut y = b;
+    time("binary Euclidean inverse", NUM_INVERSIONS, || {
+        y = y.inverse().unwrap()
+    });
+    assert_eq!(x.0, y.0);
+    time("pow(q - 2) inverse", NUM_INVERSIONS / 10, || {
+        x = x.pow(q_minus_two)
+    });
+
+    let mut x = a;
+    time("mul", NUM_PRODUCTS, || x *= &a);
+    let mut y = b;
+    time("derived mul", NUM_PRODUCTS, || y *= &b);
+    assert_eq!(x.0, y.0);
+    time("square", NUM_PRODUCTS, || {
+        x.square_in_place();
+    });
+    time("derived square", NUM_PRODUCTS, || {
+        y.square_in_place();
+    });
+    assert_eq!(x.0, y.0);
+}
+
+fn main() {
+    println!("Base field of Yafa 108:");
+    bench_base_field::<yafa_108::FqConfig, Fq108DerivedConfig>();
+    println!("Base field of Yafa 146:");
+    bench_base_field::<yafa_146::FqConfig, Fq146DerivedConfig>();
+}
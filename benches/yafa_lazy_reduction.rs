@@ -0,0 +1,95 @@
+use ark_ff::Field;
+use ark_std::time::Instant;
+use ark_std::{test_rng, UniformRand};
+
+use ark_yafa::{yafa_108, yafa_146};
+
+const NUM_PRODUCTS: usize = 10000;
+
+fn time<T, F: FnMut(&mut T)>(name: &str, mut acc: T, mut f: F) -> T {
+    let timer = Instant::now();
+    for _ in 0..NUM_PRODUCTS {
+        f(&mut acc);
+    }
+    println!(
+        "{}: {}",
+        name,
+        timer.elapsed().as_secs_f64() / NUM_PRODUCTS as f64
+    );
+    acc
+}
+
+// Each lazy product adds up the double-width products of Fq and reduces once per output
+// coefficient, where the generic tower arithmetic of ark-ff reduces after every product.
+fn bench_yafa_146() {
+    use yafa_146::{Fq12, Fq12Config, Fq2, Fq6, Fq6Config};
+
+    let mut rng = test_rng();
+    let f = Fq12::rand(&mut rng);
+    let g = Fq12::rand(&mut rng);
+    let (c0, c3, c4) = (
+        Fq2::rand(&mut rng),
+        Fq2::rand(&mut rng),
+        Fq2::rand(&mut rng),
+    );
+    let h = Fq6::rand(&mut rng);
+
+    let generic = time("Generic Fq12 multiplication", f, |a| *a *= &g);
+    let lazy = time("Lazy Fq12 multiplication", f, |a| {
+        Fq12Config::lazy_mul_assign(a, &g)
+    });
+    assert_eq!(generic, lazy);
+
+    let generic = time("Generic Fq12 squaring", f, |a| {
+        a.square_in_place();
+    });
+    let lazy = time("Lazy Fq12 squaring", f, |a| {
+        Fq12Config::lazy_square_in_place(a);
+    });
+    assert_eq!(generic, lazy);
+
+    let generic = time("Generic Fq12 sparse 034 multiplication", f, |a| {
+        a.mul_by_034(&c0, &c3, &c4)
+    });
+    let lazy = time("Lazy Fq12 sparse 034 multiplication", f, |a| {
+        Fq12Config::lazy_mul_by_034(a, &c0, &c3, &c4)
+    });
+    assert_eq!(generic, lazy);
+
+    let generic = time("Generic Fq6 squaring", h, |a| {
+        a.square_in_place();
+    });
+    let lazy = time("Lazy Fq6 squaring", h, |a| {
+        Fq6Config::lazy_square_in_place(a);
+    });
+    assert_eq!(generic, lazy);
+}
+
+fn bench_yafa_108() {
+    use yafa_108::{Fq6, Fq6Config};
+
+    let mut rng = test_rng();
+    let f = Fq6::rand(&mut rng);
+    let g = Fq6::rand(&mut rng);
+
+    let generic = time("Generic Fq6 multiplication", f, |a| *a *= &g);
+    let lazy = time("Lazy Fq6 multiplication", f, |a| {
+        Fq6Config::lazy_mul_assign(a, &g)
+    });
+    assert_eq!(generic, lazy);
+
+    let generic = time("Generic Fq6 squaring", f, |a| {
+        a.square_in_place();
+    });
+    let lazy = time("Lazy Fq6 squaring", f, |a| {
+        Fq6Config::lazy_square_in_place(a);
+    });
+    assert_eq!(generic, lazy);
+}
+
+fn main() {
+    println!("Lazy reduction on Yafa 146:");
+    bench_yafa_146();
+    println!("Lazy reduction on Yafa 108:");
+    bench_yafa_108();
+}
@@ -0,0 +1,58 @@
+//! Multi-exponentiation in the cyclotomic subgroups of the target fields of the Yafa pairings,
+//! for the hard parts of their final exponentiations.
+
+use ark_ff::{biginteger::BigInt, BigInteger, CyclotomicMultSubgroup};
+use ark_std::{iter::successors, vec::Vec};
+
+/// The window size of the multi-exponentiation in the final exponentiation.
+const MULTI_EXP_WINDOW_SIZE: usize = 5;
+
+/// Computes the product of the `bases[i]^exponents[i]`, for bases in the cyclotomic subgroup,
+/// with one chain of squarings shared by all the exponents, each written in
+/// width-`MULTI_EXP_WINDOW_SIZE` NAF.
+///
+/// `square_in_place` and `mul_assign` square and multiply in the cyclotomic subgroup, so that
+/// each tower can use its fastest arithmetic.
+pub(crate) fn cyclotomic_multi_exp<F: CyclotomicMultSubgroup, const N: usize>(
+    bases: &[F],
+    exponents: &[BigInt<N>],
+    square_in_place: fn(&mut F) -> &mut F,
+    mul_assign: fn(&mut F, &F),
+) -> F {
+    // The odd powers base, base^3, ..., base^(2^(MULTI_EXP_WINDOW_SIZE - 1) - 1).
+    let tables = bases
+        .iter()
+        .map(|base| {
+            let mut base_square = *base;
+            square_in_place(&mut base_square);
+            successors(Some(*base), |power| {
+                let mut power = *power;
+                mul_assign(&mut power, &base_square);
+                Some(power)
+            })
+            .take(1 << (MULTI_EXP_WINDOW_SIZE - 2))
+            .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let wnafs = exponents
+        .iter()
+        .map(|e| e.find_wnaf(MULTI_EXP_WINDOW_SIZE).unwrap())
+        .collect::<Vec<_>>();
+
+    let mut res = F::one();
+    let len = wnafs.iter().map(Vec::len).max().unwrap_or(0);
+    for i in (0..len).rev() {
+        square_in_place(&mut res);
+        for (table, wnaf) in tables.iter().zip(&wnafs) {
+            match wnaf.get(i) {
+                Some(&d) if d > 0 => mul_assign(&mut res, &table[(d / 2) as usize]),
+                Some(&d) if d < 0 => mul_assign(
+                    &mut res,
+                    &table[(-d / 2) as usize].cyclotomic_inverse().unwrap(),
+                ),
+                _ => {}
+            }
+        }
+    }
+    res
+}
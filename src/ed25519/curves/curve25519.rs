@@ -0,0 +1,101 @@
+use crate::ed25519::{EdwardsAffine, EdwardsParameters, Fq, FqConfig, Fr};
+use ark_ec::{
+    models::CurveConfig,
+    twisted_edwards::{MontCurveConfig, MontgomeryAffine},
+};
+use ark_ff::{BigInt, Field, MontFp, Zero};
+
+pub type Curve25519Affine = MontgomeryAffine<Curve25519Parameters>;
+
+/// Curve25519 of RFC 7748, the Montgomery curve v^2 = u^3 + 486662 * u^2 + u.
+///
+/// It is isomorphic to the Montgomery form -486664 * v^2 = u^3 + 486662 * u^2 + u of
+/// [`EdwardsParameters`] by multiplying v by SQRT_MINUS_486664, and birationally equivalent to
+/// edwards25519 by the maps of RFC 7748, Section 4.1.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Curve25519Parameters;
+
+impl CurveConfig for Curve25519Parameters {
+    type BaseField = Fq;
+    type ScalarField = Fr;
+
+    /// COFACTOR = 8
+    const COFACTOR: &'static [u64] = &[8];
+
+    /// COFACTOR_INV (mod r) =
+    /// 2713877091499598330239944961141122840321418634767465352250731601857045344121
+    const COFACTOR_INV: Fr =
+        MontFp!("2713877091499598330239944961141122840321418634767465352250731601857045344121");
+}
+
+impl MontCurveConfig for Curve25519Parameters {
+    /// COEFF_A = 486662
+    const COEFF_A: Fq = FqConfig::const_from_bigint(BigInt!("486662"));
+
+    /// COEFF_B = 1
+    const COEFF_B: Fq = FqConfig::const_from_bigint(BigInt!("1"));
+
+    type TECurveConfig = EdwardsParameters;
+}
+
+impl Curve25519Parameters {
+    /// Maps (x, y) on edwards25519 to (u, v) = ((1 + y) / (1 - y), SQRT_MINUS_486664 * u / x), or
+    /// returns `None` for the identity, which maps to the point at infinity.
+    pub fn from_edwards(p: &EdwardsAffine) -> Option<Curve25519Affine> {
+        let one = Fq::ONE;
+        if p.x.is_zero() {
+            // The point (0, -1) of order 2 maps to (0, 0).
+            return (p.y != one).then(|| Curve25519Affine::new(Fq::ZERO, Fq::ZERO));
+        }
+        let inv = ((one - p.y) * p.x).inverse().unwrap();
+        Some(Curve25519Affine::new(
+            (one + p.y) * p.x * inv,
+            SQRT_MINUS_486664 * (one + p.y) * inv,
+        ))
+    }
+
+    /// Maps (u, v) on Curve25519 to (x, y) = (SQRT_MINUS_486664 * u / v, (u - 1) / (u + 1)) on
+    /// edwards25519.
+    pub fn to_edwards(p: &Curve25519Affine) -> EdwardsAffine {
+        let one = Fq::ONE;
+        match (p.y * (p.x + one)).inverse() {
+            Some(inv) => EdwardsAffine::new_unchecked(
+                SQRT_MINUS_486664 * p.x * (p.x + one) * inv,
+                (p.x - one) * p.y * inv,
+            ),
+            // As 486662^2 - 4 and 486660 are not squares, (0, 0) is the only point with v = 0 or
+            // u = -1.
+            None => EdwardsAffine::new_unchecked(Fq::ZERO, -one),
+        }
+    }
+
+    /// Maps (u, v) on the Montgomery form of [`EdwardsParameters`] to (u, SQRT_MINUS_486664 * v).
+    pub fn from_montgomery(p: &MontgomeryAffine<EdwardsParameters>) -> Curve25519Affine {
+        Curve25519Affine::new(p.x, SQRT_MINUS_486664 * p.y)
+    }
+
+    /// Maps (u, v) on Curve25519 to (u, v / SQRT_MINUS_486664) on the Montgomery form of
+    /// [`EdwardsParameters`].
+    pub fn to_montgomery(p: &Curve25519Affine) -> MontgomeryAffine<EdwardsParameters> {
+        MontgomeryAffine::new(p.x, p.y * SQRT_MINUS_486664.inverse().unwrap())
+    }
+}
+
+/// SQRT_MINUS_486664 =
+/// 51042569399160536130206135233146329284152202253034631822681833788666877215207
+///
+/// The square root of -486664 for which the maps of RFC 7748 take the basepoint of RFC 8032 to
+/// the basepoint (9, BASEPOINT_V) of RFC 7748. This is minus the constant of the rational map of
+/// RFC 9380, Appendix D.1, which has sgn0 = 0.
+pub const SQRT_MINUS_486664: Fq = FqConfig::const_from_bigint(BigInt!(
+    "51042569399160536130206135233146329284152202253034631822681833788666877215207"
+));
+
+/// BASEPOINT_U = 9
+pub const BASEPOINT_U: Fq = FqConfig::const_from_bigint(BigInt!("9"));
+
+/// BASEPOINT_V =
+/// 14781619447589544791020593568409986887264606134616475288964881837755586237401
+pub const BASEPOINT_V: Fq = FqConfig::const_from_bigint(BigInt!(
+    "14781619447589544791020593568409986887264606134616475288964881837755586237401"
+));
@@ -1,9 +1,18 @@
-use crate::ed25519::{Fq, Fr};
+use crate::{
+    ed25519::{Fq, FqConfig, Fr},
+    hashing::{Elligator2Config, Elligator2Map, MapToCurveBasedEncoder, XmdFieldHasher},
+};
 use ark_ec::{
+    hashing::map_to_curve_hasher::MapToCurveBasedHasher,
     models::CurveConfig,
     twisted_edwards::{Affine, MontCurveConfig, Projective, TECurveConfig},
 };
-use ark_ff::MontFp;
+use ark_ff::{BigInt, BigInteger, Field, MontFp, PrimeField};
+use ark_serialize::SerializationError;
+use sha2::Sha512;
+
+pub mod curve25519;
+pub use self::curve25519::{Curve25519Affine, Curve25519Parameters};
 
 #[cfg(test)]
 mod tests;
@@ -29,13 +38,16 @@ impl CurveConfig for EdwardsParameters {
 
 impl TECurveConfig for EdwardsParameters {
     /// COEFF_A = -1
-    const COEFF_A: Fq = MontFp!("-1");
+    const COEFF_A: Fq = FqConfig::const_from_bigint(BigInt!(
+        "57896044618658097711785492504343953926634992332820282019728792003956564819948"
+    ));
 
     /// COEFF_D = -121665 / 121666
-    const COEFF_D: Fq =
-        MontFp!("37095705934669439343138083508754565189542113879843219016388785533085940283555");
+    const COEFF_D: Fq = FqConfig::const_from_bigint(BigInt!(
+        "37095705934669439343138083508754565189542113879843219016388785533085940283555"
+    ));
 
-    /// Generated randomly
+    /// The basepoint B of RFC 8032
     const GENERATOR: EdwardsAffine = EdwardsAffine::new_unchecked(GENERATOR_X, GENERATOR_Y);
 
     type MontCurveConfig = EdwardsParameters;
@@ -47,26 +59,101 @@ impl TECurveConfig for EdwardsParameters {
     }
 }
 
-// We want to emphasize that this Montgomery curve is not Curve25519.
+// We want to emphasize that this Montgomery curve is not Curve25519, which is
+// Curve25519Parameters.
 impl MontCurveConfig for EdwardsParameters {
     /// COEFF_A = 486662
-    const COEFF_A: Fq = MontFp!("486662");
+    const COEFF_A: Fq = FqConfig::const_from_bigint(BigInt!("486662"));
 
     /// COEFF_B = 57896044618658097711785492504343953926634992332820282019728792003956564333285
     /// This is not one, because ed25519 != curve25519
-    const COEFF_B: Fq =
-        MontFp!("57896044618658097711785492504343953926634992332820282019728792003956564333285");
+    const COEFF_B: Fq = FqConfig::const_from_bigint(BigInt!(
+        "57896044618658097711785492504343953926634992332820282019728792003956564333285"
+    ));
 
     type TECurveConfig = EdwardsParameters;
 }
 
+impl EdwardsParameters {
+    /// Encodes the point in 32 bytes as in RFC 8032, Section 5.1.2: y in little-endian, with the
+    /// parity of x in the top bit.
+    pub fn encode(p: &EdwardsAffine) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(&p.y.into_bigint().to_bytes_le());
+        bytes[31] |= u8::from(p.x.into_bigint().is_odd()) << 7;
+        bytes
+    }
+
+    /// Decodes a point as in RFC 8032, Section 5.1.3, which rejects the encodings with y >= p
+    /// and those of x = 0 with the top bit set, so that every point has a single encoding.
+    ///
+    /// As in RFC 8032, the point may be outside the subgroup of order r, which
+    /// `is_in_correct_subgroup_assuming_on_curve` checks.
+    pub fn decode(bytes: &[u8; 32]) -> Result<EdwardsAffine, SerializationError> {
+        let p = Self::decode_zip215(bytes)?;
+        match Self::encode(&p) == *bytes {
+            true => Ok(p),
+            false => Err(SerializationError::InvalidData),
+        }
+    }
+
+    /// Decodes a point as in ZIP 215, which also accepts the non-canonical encodings that
+    /// [`Self::decode`] rejects, by reducing y mod p and ignoring the top bit when x = 0. This
+    /// is what the Ed25519 signatures of ZIP 215 use to agree on which signatures are valid.
+    pub fn decode_zip215(bytes: &[u8; 32]) -> Result<EdwardsAffine, SerializationError> {
+        let mut y_bytes = *bytes;
+        y_bytes[31] &= 0x7f;
+        let y = Fq::from_le_bytes_mod_order(&y_bytes);
+
+        // x^2 = (y^2 - 1) / (d * y^2 + 1), where d * y^2 + 1 != 0 as d is not a square.
+        let y2 = y.square();
+        let x2 = (y2 - Fq::ONE) * (Self::COEFF_D * y2 + Fq::ONE).inverse().unwrap();
+        let x = x2.sqrt().ok_or(SerializationError::InvalidData)?;
+        match x.into_bigint().is_odd() == (bytes[31] >> 7 == 1) {
+            true => Ok(EdwardsAffine::new_unchecked(x, y)),
+            false => Ok(EdwardsAffine::new_unchecked(-x, y)),
+        }
+    }
+}
+
+impl Elligator2Config for EdwardsParameters {
+    /// Z = 2
+    const Z: Fq = FqConfig::const_from_bigint(BigInt!("2"));
+}
+
+/// The suite edwards25519_XMD:SHA-512_ELL2_RO_ of RFC 9380, hashing with expand_message_xmd with
+/// SHA-512, the Elligator 2 map and multiplication by the cofactor 8.
+pub type EdwardsSha512Hasher = MapToCurveBasedHasher<
+    EdwardsProjective,
+    XmdFieldHasher<Sha512>,
+    Elligator2Map<EdwardsParameters>,
+>;
+
+/// The suite edwards25519_XMD:SHA-512_ELL2_NU_ of RFC 9380, the nonuniform encoding with the
+/// components of [`EdwardsSha512Hasher`].
+pub type EdwardsSha512Encoder = MapToCurveBasedEncoder<
+    EdwardsProjective,
+    XmdFieldHasher<Sha512>,
+    Elligator2Map<EdwardsParameters>,
+>;
+
+/// The suite ID of [`EdwardsSha512Hasher`], to be part of the DSTs of applications.
+pub const EDWARDS_SHA512_RO_SUITE_ID: &[u8] = b"edwards25519_XMD:SHA-512_ELL2_RO_";
+
+/// The suite ID of [`EdwardsSha512Encoder`].
+pub const EDWARDS_SHA512_NU_SUITE_ID: &[u8] = b"edwards25519_XMD:SHA-512_ELL2_NU_";
+
 /// GENERATOR_X =
 /// 15112221349535400772501151409588531511454012693041857206046113283949847762202
-const GENERATOR_X: Fq =
-    MontFp!("15112221349535400772501151409588531511454012693041857206046113283949847762202");
+///
+/// The even x of the basepoint of RFC 8032.
+pub const GENERATOR_X: Fq = FqConfig::const_from_bigint(BigInt!(
+    "15112221349535400772501151409588531511454012693041857206046113283949847762202"
+));
 
 /// GENERATOR_Y =
 /// (4/5)
 /// 46316835694926478169428394003475163141307993866256225615783033603165251855960
-const GENERATOR_Y: Fq =
-    MontFp!("46316835694926478169428394003475163141307993866256225615783033603165251855960");
+pub const GENERATOR_Y: Fq = FqConfig::const_from_bigint(BigInt!(
+    "46316835694926478169428394003475163141307993866256225615783033603165251855960"
+));
@@ -1,4 +1,348 @@
-use crate::ed25519::*;
+use crate::{
+    ed25519::{
+        curve25519::{BASEPOINT_U, BASEPOINT_V},
+        *,
+    },
+    hashing::{tests::hash_to_curve_messages, Elligator2Map, XmdFieldHasher},
+};
 use ark_algebra_test_templates::*;
+use ark_ec::{
+    hashing::{map_to_curve_hasher::MapToCurve, HashToCurve},
+    twisted_edwards::{MontCurveConfig, MontgomeryAffine},
+    AffineRepr, CurveGroup,
+};
+use ark_ff::{field_hashers::HashToField, BigInteger, Field, One, PrimeField};
+use ark_std::{test_rng, vec, vec::Vec, UniformRand, Zero};
+use digest::Digest;
+use sha2::Sha512;
 
 test_group!(te; EdwardsProjective; te);
+
+fn hex_bytes(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+fn from_hex(s: &str) -> Fq {
+    Fq::from_be_bytes_mod_order(&hex_bytes(s))
+}
+
+#[test]
+fn test_hash_to_curve_sha512_ro() {
+    // The test vectors of RFC 9380, Appendix J.5.1, with u0, u1, Q0, Q1 and P.
+    let vectors = [
+        [
+            "03fef4813c8cb5f98c6eef88fae174e6e7d5380de2b007799ac7ee712d203f3a",
+            "780bdddd137290c8f589dc687795aafae35f6b674668d92bf92ae793e6a60c75",
+            "6549118f65bb617b9e8b438decedc73c496eaed496806d3b2eb9ee60b88e09a7",
+            "7315bcc8cf47ed68048d22bad602c6680b3382a08c7c5d3f439a973fb4cf9feb",
+            "31dcfc5c58aa1bee6e760bf78cbe71c2bead8cebb2e397ece0f37a3da19c9ed2",
+            "7876d81474828d8a5928b50c82420b2bd0898d819e9550c5c82c39fc9bafa196",
+            "3c3da6925a3c3c268448dcabb47ccde5439559d9599646a8260e47b1e4822fc6",
+            "09a6c8561a0b22bef63124c588ce4c62ea83a3c899763af26d795302e115dc21",
+        ],
+        [
+            "5081955c4141e4e7d02ec0e36becffaa1934df4d7a270f70679c78f9bd57c227",
+            "005bdc17a9b378b6272573a31b04361f21c371b256252ae5463119aa0b925b76",
+            "5c1525bd5d4b4e034512949d187c39d48e8cd84242aa4758956e4adc7d445573",
+            "2bf426cf7122d1a90abc7f2d108befc2ef415ce8c2d09695a7407240faa01f29",
+            "37b03bba828860c6b459ddad476c83e0f9285787a269df2156219b7e5c86210c",
+            "285ebf5412f84d0ad7bb4e136729a9ffd2195d5b8e73c0dc85110ce06958f432",
+            "608040b42285cc0d72cbb3985c6b04c935370c7361f4b7fbdb1ae7f8c1a8ecad",
+            "1a8395b88338f22e435bbd301183e7f20a5f9de643f11882fb237f88268a5531",
+        ],
+        [
+            "285ebaa3be701b79871bcb6e225ecc9b0b32dff2d60424b4c50642636a78d5b3",
+            "2e253e6a0ef658fedb8e4bd6a62d1544fd6547922acb3598ec6b369760b81b31",
+            "3ac463dd7fddb773b069c5b2b01c0f6b340638f54ee3bd92d452fcec3015b52d",
+            "7b03ba1e8db9ec0b390d5c90168a6a0b7107156c994c674b61fe696cbeb46baf",
+            "0757e7e904f5e86d2d2f4acf7e01c63827fde2d363985aa7432106f1b3a444ec",
+            "50026c96930a24961e9d86aa91ea1465398ff8e42015e2ec1fa397d416f6a1c0",
+            "6d7fabf47a2dc03fe7d47f7dddd21082c5fb8f86743cd020f3fb147d57161472",
+            "53060a3d140e7fbcda641ed3cf42c88a75411e648a1add71217f70ea8ec561a6",
+        ],
+        [
+            "4fedd25431c41f2a606952e2945ef5e3ac905a42cf64b8b4d4a83c533bf321af",
+            "02f20716a5801b843987097a8276b6d869295b2e11253751ca72c109d37485a9",
+            "703e69787ea7524541933edf41f94010a201cc841c1cce60205ec38513458872",
+            "32bb192c4f89106466f0874f5fd56a0d6b6f101cb714777983336c159a9bec75",
+            "0c9077c5c31720ed9413abe59bf49ce768506128d810cb882435aa90f713ef6b",
+            "7d5aec5210db638c53f050597964b74d6dda4be5b54fa73041bf909ccb3826cb",
+            "5fb0b92acedd16f3bcb0ef83f5c7b7a9466b5f1e0d8d217421878ea3686f8524",
+            "2eca15e355fcfa39d2982f67ddb0eea138e2994f5956ed37b7f72eea5e89d2f7",
+        ],
+        [
+            "6e34e04a5106e9bd59f64aba49601bf09d23b27f7b594e56d5de06df4a4ea33b",
+            "1c1c2cb59fc053f44b86c5d5eb8c1954b64976d0302d3729ff66e84068f5fd96",
+            "21091b2e3f9258c7dfa075e7ae513325a94a3d8a28e1b1cb3b5b6f5d65675592",
+            "41a33d324c89f570e0682cdf7bdb78852295daf8084c669f2cc9692896ab5026",
+            "4c07ec48c373e39a23bd7954f9e9b66eeab9e5ee1279b867b3d5315aa815454f",
+            "67ccac7c3cb8d1381242d8d6585c57eabaddbb5dca5243a68a8aeb5477d94b3a",
+            "0efcfde5898a839b00997fbe40d2ebe950bc81181afbd5cd6b9618aa336c1e8c",
+            "6dc2fc04f266c5c27f236a80b14f92ccd051ef1ff027f26a07f8c0f327d8f995",
+        ],
+    ];
+    let dst = [&b"QUUX-V01-CS02-with-"[..], EDWARDS_SHA512_RO_SUITE_ID].concat();
+    let field_hasher = <XmdFieldHasher<Sha512> as HashToField<Fq>>::new(&dst);
+    let map = <Elligator2Map<EdwardsParameters> as MapToCurve<_>>::new().unwrap();
+    let hasher = EdwardsSha512Hasher::new(&dst).unwrap();
+
+    for (msg, vector) in hash_to_curve_messages().iter().zip(vectors) {
+        let [u0, u1, q0_x, q0_y, q1_x, q1_y, p_x, p_y] = vector.map(from_hex);
+        let u: Vec<Fq> = field_hasher.hash_to_field(msg, 2);
+        assert_eq!(u, vec![u0, u1]);
+        assert_eq!(
+            map.map_to_curve(u0).unwrap(),
+            EdwardsAffine::new_unchecked(q0_x, q0_y)
+        );
+        assert_eq!(
+            map.map_to_curve(u1).unwrap(),
+            EdwardsAffine::new_unchecked(q1_x, q1_y)
+        );
+        assert_eq!(hasher.hash(msg).unwrap(), EdwardsAffine::new(p_x, p_y));
+    }
+}
+
+#[test]
+fn test_hash_to_curve_sha512_nu() {
+    // The test vectors of RFC 9380, Appendix J.5.2, with u, Q and P.
+    let vectors = [
+        [
+            "7f3e7fb9428103ad7f52db32f9df32505d7b427d894c5093f7a0f0374a30641d",
+            "42836f691d05211ebc65ef8fcf01e0fb6328ec9c4737c26050471e50803022eb",
+            "22cb4aaa555e23bd460262d2130d6a3c9207aa8bbb85060928beb263d6d42a95",
+            "1ff2b70ecf862799e11b7ae744e3489aa058ce805dd323a936375a84695e76da",
+            "222e314d04a4d5725e9f2aff9fb2a6b69ef375a1214eb19021ceab2d687f0f9b",
+        ],
+        [
+            "09cfa30ad79bd59456594a0f5d3a76f6b71c6787b04de98be5cd201a556e253b",
+            "333e41b61c6dd43af220c1ac34a3663e1cf537f996bab50ab66e33c4bd8e4e19",
+            "51b6f178eb08c4a782c820e306b82c6e273ab22e258d972cd0c511787b2a3443",
+            "5f13cc69c891d86927eb37bd4afc6672360007c63f68a33ab423a3aa040fd2a8",
+            "67732d50f9a26f73111dd1ed5dba225614e538599db58ba30aaea1f5c827fa42",
+        ],
+        [
+            "475ccff99225ef90d78cc9338e9f6a6bb7b17607c0c4428937de75d33edba941",
+            "55186c242c78e7d0ec5b6c9553f04c6aeef64e69ec2e824472394da32647cfc6",
+            "5b9ea3c265ee42256a8f724f616307ef38496ef7eba391c08f99f3bea6fa88f0",
+            "1dd2fefce934ecfd7aae6ec998de088d7dd03316aa1847198aecf699ba6613f1",
+            "2f8a6c24dd1adde73909cada6a4a137577b0f179d336685c4a955a0a8e1a86fb",
+        ],
+        [
+            "049a1c8bd51bcb2aec339f387d1ff51428b88d0763a91bcdf6929814ac95d03d",
+            "024b6e1621606dca8071aa97b43dce4040ca78284f2a527dcf5d0fbfac2b07e7",
+            "5102353883d739bdc9f8a3af650342b171217167dcce34f8db57208ec1dfdbf2",
+            "35fbdc5143e8a97afd3096f2b843e07df72e15bfca2eaf6879bf97c5d3362f73",
+            "2af6ff6ef5ebba128b0774f4296cb4c2279a074658b083b8dcca91f57a603450",
+        ],
+        [
+            "3cb0178a8137cefa5b79a3a57c858d7eeeaa787b2781be4a362a2f0750d24fa0",
+            "3e6368cff6e88a58e250c54bd27d2c989ae9b3acb6067f2651ad282ab8c21cd9",
+            "38fb39f1566ca118ae6c7af42810c0bb9767ae5960abb5a8ca792530bfb9447d",
+            "6e5e1f37e99345887fc12111575fc1c3e36df4b289b8759d23af14d774b66bff",
+            "2c90c3d39eb18ff291d33441b35f3262cdd307162cc97c31bfcc7a4245891a37",
+        ],
+    ];
+    let dst = [&b"QUUX-V01-CS02-with-"[..], EDWARDS_SHA512_NU_SUITE_ID].concat();
+    let field_hasher = <XmdFieldHasher<Sha512> as HashToField<Fq>>::new(&dst);
+    let map = <Elligator2Map<EdwardsParameters> as MapToCurve<_>>::new().unwrap();
+    let encoder = EdwardsSha512Encoder::new(&dst).unwrap();
+
+    for (msg, vector) in hash_to_curve_messages().iter().zip(vectors) {
+        let [u0, q_x, q_y, p_x, p_y] = vector.map(from_hex);
+        let u: Vec<Fq> = field_hasher.hash_to_field(msg, 1);
+        assert_eq!(u, vec![u0]);
+        assert_eq!(
+            map.map_to_curve(u0).unwrap(),
+            EdwardsAffine::new_unchecked(q_x, q_y)
+        );
+        assert_eq!(encoder.hash(msg).unwrap(), EdwardsAffine::new(p_x, p_y));
+    }
+}
+
+#[test]
+fn test_rfc8032_vectors() {
+    // The test vectors 1 to 3 of RFC 8032, Section 7.1, with the secret key, the public key, the
+    // message and the signature.
+    let vectors = [
+        [
+            "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+            "",
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+        ],
+        [
+            "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+            "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+            "72",
+            "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+        ],
+        [
+            "c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+            "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+            "af82",
+            "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a",
+        ],
+    ];
+    let base = EdwardsAffine::generator();
+
+    for [secret, public, msg, signature] in vectors {
+        let [public, msg, signature] = [public, msg, signature].map(hex_bytes);
+        let h = Sha512::digest(hex_bytes(secret));
+
+        // The secret scalar is the first half of the hash with the bits 0, 1, 2 and 255 cleared
+        // and the bit 254 set.
+        let mut a = [0u8; 32];
+        a.copy_from_slice(&h[..32]);
+        a[0] &= 248;
+        a[31] &= 127;
+        a[31] |= 64;
+        let a = Fr::from_le_bytes_mod_order(&a);
+        let pk = (base * a).into_affine();
+        let public: [u8; 32] = public.try_into().unwrap();
+        assert_eq!(EdwardsParameters::encode(&pk), public);
+        assert_eq!(EdwardsParameters::decode(&public).unwrap(), pk);
+        assert_eq!(EdwardsParameters::decode_zip215(&public).unwrap(), pk);
+
+        let r_bytes: [u8; 32] = signature[..32].try_into().unwrap();
+        let s_bytes: [u8; 32] = signature[32..].try_into().unwrap();
+        let r = EdwardsParameters::decode(&r_bytes).unwrap();
+        let s = FrConfig::decode(&s_bytes).unwrap();
+        assert_eq!(EdwardsParameters::encode(&r), r_bytes);
+        assert_eq!(FrConfig::encode(&s), s_bytes);
+
+        // The nonce and the challenge are the wide reductions of SHA-512 hashes.
+        let hash = |parts: &[&[u8]]| -> [u8; 64] {
+            let mut hasher = Sha512::new();
+            for part in parts {
+                hasher.update(part);
+            }
+            hasher.finalize().into()
+        };
+        let nonce = FrConfig::from_bytes_wide(&hash(&[&h[32..], &msg]));
+        let k = FrConfig::from_bytes_wide(&hash(&[&r_bytes, &public, &msg]));
+        assert_eq!((base * nonce).into_affine(), r);
+        assert_eq!(s, nonce + k * a);
+        assert_eq!(base * s, r + pk * k);
+    }
+}
+
+#[test]
+fn test_point_encoding() {
+    let mut rng = test_rng();
+    for _ in 0..20 {
+        let p = EdwardsProjective::rand(&mut rng).into_affine();
+        let bytes = EdwardsParameters::encode(&p);
+        assert_eq!(EdwardsParameters::decode(&bytes).unwrap(), p);
+        assert_eq!(EdwardsParameters::decode_zip215(&bytes).unwrap(), p);
+    }
+
+    // y = 1 with the top bit set, y = p + 1 and y = p, which only ZIP 215 accepts.
+    let mut one_negative = [0u8; 32];
+    one_negative[0] = 1;
+    one_negative[31] = 0x80;
+    let mut p_plus_one = [0xffu8; 32];
+    p_plus_one[0] = 0xee;
+    p_plus_one[31] = 0x7f;
+    let mut p = p_plus_one;
+    p[0] = 0xed;
+    for (bytes, y) in [(one_negative, 1u8), (p_plus_one, 1), (p, 0)] {
+        assert!(EdwardsParameters::decode(&bytes).is_err());
+        let point = EdwardsParameters::decode_zip215(&bytes).unwrap();
+        assert_eq!(point.y, Fq::from(y));
+        assert!(point.is_on_curve());
+    }
+    assert!(EdwardsParameters::decode_zip215(&p_plus_one)
+        .unwrap()
+        .is_zero());
+
+    // y = 2 is not on the curve, and y = p - 1 gives the point (0, -1) of order 2.
+    let mut two = [0u8; 32];
+    two[0] = 2;
+    assert!(EdwardsParameters::decode(&two).is_err());
+    assert!(EdwardsParameters::decode_zip215(&two).is_err());
+    let mut minus_one = p;
+    minus_one[0] = 0xec;
+    let point = EdwardsParameters::decode(&minus_one).unwrap();
+    assert_eq!(
+        point,
+        EdwardsAffine::new_unchecked(Fq::zero(), -Fq::from(1u8))
+    );
+    assert!(!point.is_in_correct_subgroup_assuming_on_curve());
+}
+
+/// Checks that (u, v) is on the Montgomery curve B * v^2 = u^3 + A * u^2 + u.
+fn is_on_montgomery_curve<P: MontCurveConfig>(p: &MontgomeryAffine<P>) -> bool {
+    P::COEFF_B * p.y.square() == ((p.x + P::COEFF_A) * p.x + P::BaseField::one()) * p.x
+}
+
+#[test]
+fn test_rfc8032_basepoint() {
+    // The encoding of B in RFC 8032, Section 5.1, of y = 4 / 5 and an even x.
+    let mut bytes = [0x66u8; 32];
+    bytes[0] = 0x58;
+    let base = EdwardsParameters::decode(&bytes).unwrap();
+    assert_eq!(base, EdwardsAffine::generator());
+    assert_eq!(base.y * Fq::from(5u8), Fq::from(4u8));
+    assert!(base.is_in_correct_subgroup_assuming_on_curve());
+}
+
+#[test]
+fn test_curve25519_basepoint() {
+    let base = Curve25519Parameters::from_edwards(&EdwardsAffine::generator()).unwrap();
+    assert_eq!(base.x, Fq::from(9u8));
+    assert_eq!(base, Curve25519Affine::new(BASEPOINT_U, BASEPOINT_V));
+    assert!(is_on_montgomery_curve(&base));
+    assert_eq!(
+        Curve25519Parameters::to_edwards(&base),
+        EdwardsAffine::generator()
+    );
+
+    // The X25519 public keys of RFC 7748, Section 6.1, are the u-coordinates of [k]B for the
+    // clamped secret keys k.
+    for (secret, public) in [
+        (
+            "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a",
+            "8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a",
+        ),
+        (
+            "5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb",
+            "de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f",
+        ),
+    ] {
+        let mut k = hex_bytes(secret);
+        k[0] &= 248;
+        k[31] &= 127;
+        k[31] |= 64;
+        let p = (EdwardsAffine::generator() * Fr::from_le_bytes_mod_order(&k)).into_affine();
+        let u = Curve25519Parameters::from_edwards(&p).unwrap().x;
+        assert_eq!(u.into_bigint().to_bytes_le(), hex_bytes(public));
+    }
+}
+
+#[test]
+fn test_curve25519_maps() {
+    let mut rng = test_rng();
+    for _ in 0..20 {
+        let p = EdwardsProjective::rand(&mut rng).into_affine();
+        let q = Curve25519Parameters::from_edwards(&p).unwrap();
+        assert!(is_on_montgomery_curve(&q));
+        assert_eq!(Curve25519Parameters::to_edwards(&q), p);
+
+        // The Montgomery form of EdwardsParameters, with u = (1 + y) / (1 - y) and v = u / x.
+        let u = (Fq::one() + p.y) / (Fq::one() - p.y);
+        let r = MontgomeryAffine::<EdwardsParameters>::new(u, u / p.x);
+        assert!(is_on_montgomery_curve(&r));
+        assert_eq!(Curve25519Parameters::from_montgomery(&r), q);
+        assert_eq!(Curve25519Parameters::to_montgomery(&q), r);
+    }
+
+    // The identity has no affine image, and the point of order 2 maps to (0, 0).
+    assert!(Curve25519Parameters::from_edwards(&EdwardsAffine::zero()).is_none());
+    let minus_one = EdwardsAffine::new_unchecked(Fq::zero(), -Fq::one());
+    let origin = Curve25519Affine::new(Fq::zero(), Fq::zero());
+    assert_eq!(Curve25519Parameters::from_edwards(&minus_one), Some(origin));
+    assert_eq!(Curve25519Parameters::to_edwards(&origin), minus_one);
+}
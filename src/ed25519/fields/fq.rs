@@ -1,7 +1,270 @@
-use ark_ff::fields::{Fp256, MontBackend, MontConfig};
+use ark_ff::{
+    biginteger::BigInt,
+    fields::{Field, Fp, Fp256, FpConfig, SqrtPrecomputation},
+};
+use ark_std::marker::PhantomData;
 
-#[derive(MontConfig)]
-#[modulus = "57896044618658097711785492504343953926634992332820282019728792003956564819949"]
-#[generator = "2"]
+/// The field of integers modulo the pseudo-Mersenne prime p = 2^255 - 19.
+///
+/// Elements are stored in canonical form rather than in Montgomery form. A product is reduced
+/// by folding its high half onto its low half using 2^256 = 38 (mod p), and then the bits from
+/// 2^255 on using 2^255 = 19 (mod p).
 pub struct FqConfig;
-pub type Fq = Fp256<MontBackend<FqConfig, 4>>;
+pub type Fq = Fp256<FqConfig>;
+
+/// p = 2^255 - 19
+const MODULUS: BigInt<4> = BigInt::new([
+    0xffffffffffffffed,
+    0xffffffffffffffff,
+    0xffffffffffffffff,
+    0x7fffffffffffffff,
+]);
+
+/// (t - 1) / 2, where p - 1 = 2^2 * t
+const TRACE_MINUS_ONE_DIV_TWO: [u64; 4] = [
+    0xfffffffffffffffd,
+    0xffffffffffffffff,
+    0xffffffffffffffff,
+    0x0fffffffffffffff,
+];
+
+impl FqConfig {
+    /// Returns the field element with the canonical representation `element`, for use in
+    /// constants the way `MontFp!` is used for Montgomery fields.
+    ///
+    /// Panics if `element` is not less than the modulus.
+    pub const fn const_from_bigint(element: BigInt<4>) -> Fq {
+        let mut i = 4;
+        while i > 0 {
+            i -= 1;
+            if element.0[i] < MODULUS.0[i] {
+                return Fp(element, PhantomData);
+            }
+            assert!(
+                element.0[i] == MODULUS.0[i],
+                "element is not less than the modulus"
+            );
+        }
+        panic!("element is not less than the modulus")
+    }
+}
+
+impl FpConfig<4> for FqConfig {
+    const MODULUS: BigInt<4> = MODULUS;
+    const GENERATOR: Fq = Fp(BigInt::new([2, 0, 0, 0]), PhantomData);
+    const ZERO: Fq = Fp(BigInt::new([0, 0, 0, 0]), PhantomData);
+    const ONE: Fq = Fp(BigInt::new([1, 0, 0, 0]), PhantomData);
+    const TWO_ADICITY: u32 = 2;
+
+    /// 2^t = sqrt(-1) =
+    /// 19681161376707505956807079304988542015446066515923890162744021073123829784752
+    const TWO_ADIC_ROOT_OF_UNITY: Fq = Fp(
+        BigInt::new([
+            0xc4ee1b274a0ea0b0,
+            0x2f431806ad2fe478,
+            0x2b4d00993dfbd7a7,
+            0x2b8324804fc1df0b,
+        ]),
+        PhantomData,
+    );
+
+    const SQRT_PRECOMP: Option<SqrtPrecomputation<Fq>> = Some(SqrtPrecomputation::TonelliShanks {
+        two_adicity: 2,
+        quadratic_nonresidue_to_trace: Self::TWO_ADIC_ROOT_OF_UNITY,
+        trace_of_modulus_minus_one_div_two: &TRACE_MINUS_ONE_DIV_TWO,
+    });
+
+    #[inline]
+    fn add_assign(a: &mut Fq, b: &Fq) {
+        // Both summands are below 2^255, so the sum fits into four limbs.
+        let mut carry = 0;
+        for i in 0..4 {
+            (a.0).0[i] = adc((a.0).0[i], (b.0).0[i], &mut carry);
+        }
+        (a.0).0 = subtract_modulus_if_geq((a.0).0);
+    }
+
+    #[inline]
+    fn sub_assign(a: &mut Fq, b: &Fq) {
+        let mut borrow = 0;
+        for i in 0..4 {
+            (a.0).0[i] = sbb((a.0).0[i], (b.0).0[i], &mut borrow);
+        }
+
+        // Add p back if the difference is negative.
+        let mask = 0u64.wrapping_sub(borrow);
+        let mut carry = 0;
+        for i in 0..4 {
+            (a.0).0[i] = adc((a.0).0[i], MODULUS.0[i] & mask, &mut carry);
+        }
+    }
+
+    #[inline]
+    fn double_in_place(a: &mut Fq) {
+        let b = *a;
+        Self::add_assign(a, &b);
+    }
+
+    #[inline]
+    fn neg_in_place(a: &mut Fq) {
+        let limbs = (a.0).0;
+        let is_zero = (limbs[0] | limbs[1] | limbs[2] | limbs[3]) == 0;
+        let mask = (is_zero as u64).wrapping_sub(1);
+        let mut borrow = 0;
+        for (limb, p) in (a.0).0.iter_mut().zip(MODULUS.0) {
+            *limb = sbb(p, *limb, &mut borrow) & mask;
+        }
+    }
+
+    #[inline]
+    fn mul_assign(a: &mut Fq, b: &Fq) {
+        let (a_limbs, b_limbs) = ((a.0).0, (b.0).0);
+        let mut t = [0u64; 8];
+        for i in 0..4 {
+            let mut carry = 0;
+            for j in 0..4 {
+                t[i + j] = mac(t[i + j], a_limbs[i], b_limbs[j], &mut carry);
+            }
+            t[i + 4] = carry;
+        }
+        (a.0).0 = reduce_wide(t);
+    }
+
+    #[inline]
+    fn sum_of_products<const T: usize>(a: &[Fq; T], b: &[Fq; T]) -> Fq {
+        a.iter().zip(b).fold(Self::ZERO, |mut sum, (a, b)| {
+            Self::add_assign(&mut sum, &(*a * b));
+            sum
+        })
+    }
+
+    #[inline]
+    fn square_in_place(a: &mut Fq) {
+        let limbs = (a.0).0;
+        let mut t = [0u64; 8];
+
+        // The products of distinct limbs, which appear twice in the square.
+        for i in 0..3 {
+            let mut carry = 0;
+            for j in (i + 1)..4 {
+                t[i + j] = mac(t[i + j], limbs[i], limbs[j], &mut carry);
+            }
+            t[i + 4] = carry;
+        }
+        t[7] = t[6] >> 63;
+        for i in (2..7).rev() {
+            t[i] = (t[i] << 1) | (t[i - 1] >> 63);
+        }
+        t[1] <<= 1;
+
+        // The squares of the limbs.
+        let mut carry = 0;
+        for i in 0..4 {
+            t[2 * i] = mac(t[2 * i], limbs[i], limbs[i], &mut carry);
+            t[2 * i + 1] = adc(t[2 * i + 1], 0, &mut carry);
+        }
+        (a.0).0 = reduce_wide(t);
+    }
+
+    fn inverse(a: &Fq) -> Option<Fq> {
+        if *a == Self::ZERO {
+            return None;
+        }
+
+        // a^(p - 2) = a^(2^255 - 21), with the addition chain of ref10.
+        let z2 = a.square();
+        let z9 = square_n(z2, 2) * a;
+        let z11 = z9 * z2;
+        let z_5_0 = z11.square() * z9;
+        let z_10_0 = square_n(z_5_0, 5) * z_5_0;
+        let z_20_0 = square_n(z_10_0, 10) * z_10_0;
+        let z_40_0 = square_n(z_20_0, 20) * z_20_0;
+        let z_50_0 = square_n(z_40_0, 10) * z_10_0;
+        let z_100_0 = square_n(z_50_0, 50) * z_50_0;
+        let z_200_0 = square_n(z_100_0, 100) * z_100_0;
+        let z_250_0 = square_n(z_200_0, 50) * z_50_0;
+        Some(square_n(z_250_0, 5) * z11)
+    }
+
+    fn from_bigint(other: BigInt<4>) -> Option<Fq> {
+        (other < MODULUS).then_some(Fp(other, PhantomData))
+    }
+
+    #[inline]
+    fn into_bigint(other: Fq) -> BigInt<4> {
+        other.0
+    }
+}
+
+/// Returns a + b * c + carry, and sets carry to the high limb.
+#[inline(always)]
+fn mac(a: u64, b: u64, c: u64, carry: &mut u64) -> u64 {
+    let t = (a as u128) + (b as u128) * (c as u128) + (*carry as u128);
+    *carry = (t >> 64) as u64;
+    t as u64
+}
+
+/// Returns a + b + carry, and sets carry to the high limb.
+#[inline(always)]
+fn adc(a: u64, b: u64, carry: &mut u64) -> u64 {
+    let t = (a as u128) + (b as u128) + (*carry as u128);
+    *carry = (t >> 64) as u64;
+    t as u64
+}
+
+/// Returns a - b - borrow, and sets borrow to 1 if this wraps around and to 0 otherwise.
+#[inline(always)]
+fn sbb(a: u64, b: u64, borrow: &mut u64) -> u64 {
+    let t = (a as u128).wrapping_sub((b as u128) + (*borrow as u128));
+    *borrow = (t >> 127) as u64;
+    t as u64
+}
+
+/// Reduces a < 2p to the range [0, p), without branching on a.
+#[inline(always)]
+fn subtract_modulus_if_geq(a: [u64; 4]) -> [u64; 4] {
+    // a >= p exactly if a + 19 >= 2^255, and then a - p = a + 19 - 2^255.
+    let mut b = [0u64; 4];
+    let mut carry = 19;
+    for i in 0..4 {
+        b[i] = adc(a[i], 0, &mut carry);
+    }
+    let mask = 0u64.wrapping_sub(b[3] >> 63);
+    b[3] &= 0x7fffffffffffffff;
+    for i in 0..4 {
+        b[i] = (b[i] & mask) | (a[i] & !mask);
+    }
+    b
+}
+
+/// Reduces a product t < p^2 modulo p.
+#[inline(always)]
+fn reduce_wide(t: [u64; 8]) -> [u64; 4] {
+    // t = lo + 2^256 * hi = lo + 38 * hi (mod p)
+    let mut r = [0u64; 4];
+    let mut carry = 0;
+    for i in 0..4 {
+        r[i] = mac(t[i], t[i + 4], 38, &mut carry);
+    }
+
+    // The result is below 39 * 2^256, so at most the 7 bits from 2^255 on remain to be folded.
+    let top = (carry << 1) | (r[3] >> 63);
+    r[3] &= 0x7fffffffffffffff;
+    let mut carry = 0;
+    r[0] = mac(r[0], top, 19, &mut carry);
+    for limb in &mut r[1..] {
+        *limb = adc(*limb, 0, &mut carry);
+    }
+
+    // Now r < 2^255 + 19 * 2^7 < 2p.
+    subtract_modulus_if_geq(r)
+}
+
+/// Returns a^(2^n).
+#[inline]
+fn square_n(mut a: Fq, n: usize) -> Fq {
+    for _ in 0..n {
+        a.square_in_place();
+    }
+    a
+}
@@ -1,7 +1,32 @@
-use ark_ff::fields::{Fp256, MontBackend, MontConfig};
+use ark_ff::{
+    fields::{Fp256, MontBackend, MontConfig},
+    BigInteger, PrimeField,
+};
+use ark_serialize::{CanonicalDeserialize, SerializationError};
 
 #[derive(MontConfig)]
 #[modulus = "7237005577332262213973186563042994240857116359379907606001950938285454250989"]
 #[generator = "2"]
 pub struct FrConfig;
 pub type Fr = Fp256<MontBackend<FrConfig, 4>>;
+
+impl FrConfig {
+    /// Encodes the scalar in 32 bytes in little-endian, as in RFC 8032.
+    pub fn encode(fe: &Fr) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(&fe.into_bigint().to_bytes_le());
+        bytes
+    }
+
+    /// Decodes a scalar from 32 little-endian bytes, rejecting the integers not below r, as the
+    /// verification of the scalar S of a signature in RFC 8032, Section 5.1.7, requires.
+    pub fn decode(bytes: &[u8; 32]) -> Result<Fr, SerializationError> {
+        Fr::deserialize_compressed(&bytes[..])
+    }
+
+    /// Reduces 64 little-endian bytes modulo r, as RFC 8032 does with the SHA-512 hashes that
+    /// give the nonce and the challenge of a signature.
+    pub fn from_bytes_wide(bytes: &[u8; 64]) -> Fr {
+        Fr::from_le_bytes_mod_order(bytes)
+    }
+}
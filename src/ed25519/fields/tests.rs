@@ -1,5 +1,117 @@
-use crate::ed25519::{Fq, Fr};
+use crate::ed25519::{Fq, FqConfig, Fr, FrConfig};
 use ark_algebra_test_templates::*;
+use ark_ff::{
+    fields::{Fp256, FpConfig, MontBackend, MontConfig},
+    BigInt, BigInteger, Field, MontFp, PrimeField,
+};
+use ark_std::{test_rng, vec::Vec, UniformRand, Zero};
 
 test_field!(fr; Fr; mont_prime_field);
-test_field!(fq; Fq; mont_prime_field);
+test_field!(fq; Fq; prime_field);
+
+#[derive(MontConfig)]
+#[modulus = "57896044618658097711785492504343953926634992332820282019728792003956564819949"]
+#[generator = "2"]
+struct MontFqConfig;
+type MontFq = Fp256<MontBackend<MontFqConfig, 4>>;
+
+#[test]
+fn test_fq_matches_montgomery_backend() {
+    let to_mont = |a: &Fq| MontFq::from_bigint(a.into_bigint()).unwrap();
+
+    let mut rng = test_rng();
+
+    // Elements at and close to the reduction boundaries, followed by random ones.
+    let mut elements: Vec<Fq> = [
+        BigInt!("0"),
+        BigInt!("1"),
+        BigInt!("19"),
+        BigInt!("340282366920938463463374607431768211455"),
+        BigInt!("28948022309329048855892746252171976963317496166410141009864396001978282409984"),
+        BigInt!("57896044618658097711785492504343953926634992332820282019728792003956564819930"),
+        BigInt!("57896044618658097711785492504343953926634992332820282019728792003956564819948"),
+    ]
+    .iter()
+    .map(|&a| Fq::from_bigint(a).unwrap())
+    .collect();
+    elements.extend((0..100).map(|_| Fq::rand(&mut rng)));
+
+    for a in &elements {
+        let a_mont = to_mont(a);
+        assert_eq!(to_mont(&-*a), -a_mont);
+        assert_eq!(to_mont(&a.double()), a_mont.double());
+        assert_eq!(to_mont(&a.square()), a_mont.square());
+        assert_eq!(a.inverse().map(|b| to_mont(&b)), a_mont.inverse());
+        assert_eq!(
+            a.sqrt().map(|b| to_mont(&b).square()),
+            a_mont.sqrt().map(|b| b.square())
+        );
+
+        for b in &elements {
+            let b_mont = to_mont(b);
+            assert_eq!(to_mont(&(*a + b)), a_mont + b_mont);
+            assert_eq!(to_mont(&(*a - b)), a_mont - b_mont);
+            assert_eq!(to_mont(&(*a * b)), a_mont * b_mont);
+        }
+    }
+
+    assert_eq!(
+        to_mont(&FqConfig::sum_of_products(
+            &[elements[5], elements[6]],
+            &[elements[6], elements[3]]
+        )),
+        to_mont(&elements[5]) * to_mont(&elements[6])
+            + to_mont(&elements[6]) * to_mont(&elements[3])
+    );
+}
+
+#[test]
+fn test_fq_const_from_bigint() {
+    let p_minus_one =
+        BigInt!("57896044618658097711785492504343953926634992332820282019728792003956564819948");
+    assert_eq!(FqConfig::const_from_bigint(p_minus_one), -Fq::from(1u64));
+    assert!(FqConfig::const_from_bigint(BigInt!("0")).is_zero());
+}
+
+#[test]
+#[should_panic]
+fn test_fq_const_from_bigint_rejects_modulus() {
+    FqConfig::const_from_bigint(FqConfig::MODULUS);
+}
+
+#[test]
+fn test_fr_encoding() {
+    let mut rng = test_rng();
+    for _ in 0..20 {
+        let s = Fr::rand(&mut rng);
+        assert_eq!(FrConfig::decode(&FrConfig::encode(&s)).unwrap(), s);
+    }
+
+    // r itself is rejected, and r - 1 is the largest scalar.
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&Fr::MODULUS.to_bytes_le());
+    assert!(FrConfig::decode(&bytes).is_err());
+    bytes[0] -= 1;
+    assert_eq!(FrConfig::decode(&bytes).unwrap(), -Fr::from(1u8));
+    assert!(FrConfig::decode(&[0xff; 32]).is_err());
+}
+
+#[test]
+fn test_fr_from_bytes_wide() {
+    // (2^512 - 1) mod r
+    assert_eq!(
+        FrConfig::from_bytes_wide(&[0xff; 64]),
+        MontFp!("1627715501170711445284395025044413883736156588369414752970002579683115011840")
+    );
+
+    // lo + 2^256 * hi for random lo, hi < r.
+    let mut rng = test_rng();
+    let two_256 = Fr::from(2u8).pow([256]);
+    for _ in 0..20 {
+        let [lo, hi] = [Fr::rand(&mut rng), Fr::rand(&mut rng)];
+        let mut bytes = [0u8; 64];
+        bytes[..32].copy_from_slice(&FrConfig::encode(&lo));
+        bytes[32..].copy_from_slice(&FrConfig::encode(&hi));
+        assert_eq!(FrConfig::from_bytes_wide(&bytes), lo + two_256 * hi);
+    }
+}
@@ -0,0 +1,398 @@
+//! GLV and GLS scalar multiplication for the curves over the Yafa base fields and their
+//! extensions, of which the groups have the scalar field Fr = 2^255 - 19, after Gallant, Lambert
+//! and Vanstone, "Faster Point Multiplication on Elliptic Curves with Efficient Endomorphisms", and
+//! Galbraith, Lin and Scott, "Endomorphisms for Faster Elliptic Curve Cryptography on a Large
+//! Class of Curves".
+//!
+//! For a primitive cube root of unity beta in the base field, phi(x, y) = (beta * x, y) is an
+//! endomorphism which acts on the subgroup of order r as multiplication by a cube root of unity
+//! lambda mod r. A scalar k splits as k = k1 + k2 * lambda with k1 and k2 of at most 128 bits,
+//! which halves the number of doublings.
+//!
+//! More generally, an endomorphism which acts as multiplication by mu splits k into D parts with
+//! k = k_0 + k_1 * mu + ... + k_{D-1} * mu^(D-1), as for the untwist-Frobenius-twist
+//! endomorphism psi of a G2 over Fq2, where q has order 12 mod r and D = 4.
+
+use ark_ec::{
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    Group,
+};
+use ark_ff::{fields::BitIteratorBE, BigInt, BigInteger, PrimeField, Zero};
+use ark_std::{cfg_into_iter, cfg_iter, vec, vec::Vec};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::ed25519::{Fq as Fr, FqConfig as FrConfig};
+
+#[cfg(test)]
+pub(crate) mod tests;
+
+/// LAMBDA = 25380276437079137597092236364571181010632177832931468165172742469126098314552
+///
+/// The cube root of unity mod r by which the endomorphism phi acts.
+pub(crate) const LAMBDA: Fr = FrConfig::const_from_bigint(BigInt!(
+    "25380276437079137597092236364571181010632177832931468165172742469126098314552"
+));
+
+/// The decomposition k = k1 + k2 * lambda, with the reduced basis (a1, b1), (a2, b2) of the
+/// lattice of (x, y) with x + y * lambda = 0 mod r, with entries below 2^128 in absolute value:
+///
+/// a1 = 167089731525863132650062706797393049103,
+/// b1 = -108696677178804449820597827182464518980,
+/// a2 = 108696677178804449820597827182464518980,
+/// b2 = 275786408704667582470660533979857568083,
+///
+/// and round(2^256 * b2 / r) = 551572817409335164941321067959715136166 and
+/// round(-2^256 * b1 / r) = 217393354357608899641195654364929037960.
+pub(crate) const GLV_DECOMPOSITION: Decomposition<2> = Decomposition {
+    eigenvalue: LAMBDA,
+    basis: [
+        [
+            FrConfig::const_from_bigint(BigInt!("167089731525863132650062706797393049103")),
+            FrConfig::const_from_bigint(BigInt!(
+                "57896044618658097711785492504343953926526295655641477569908194176774100300969"
+            )),
+        ],
+        [
+            FrConfig::const_from_bigint(BigInt!("108696677178804449820597827182464518980")),
+            FrConfig::const_from_bigint(BigInt!("275786408704667582470660533979857568083")),
+        ],
+    ],
+    rounding: [
+        (false, [0xc758a95f37a882a6, 0x9ef50f8096dd2623, 0x1]),
+        (false, [0x1826ee730d9d9688, 0xa38c6fe4ebd45677, 0x0]),
+    ],
+    num_bits: 128,
+};
+
+/// The window width of the wNAF digits in scalar multiplication, which uses tables of
+/// 2^(WNAF_WINDOW - 2) odd multiples.
+const WNAF_WINDOW: usize = 4;
+
+/// The decomposition of scalars k = k_0 + k_1 * mu + ... + k_{D-1} * mu^(D-1) mod r for an
+/// eigenvalue mu, by rounding the coordinates of (k, 0, ..., 0) in a reduced basis of the lattice
+/// of (x_0, ..., x_{D-1}) with x_0 + x_1 * mu + ... + x_{D-1} * mu^(D-1) = 0 mod r.
+pub(crate) struct Decomposition<const D: usize> {
+    /// The eigenvalue mu.
+    pub(crate) eigenvalue: Fr,
+    /// The rows of the reduced basis.
+    pub(crate) basis: [[Fr; D]; D],
+    /// The signs (true for negative) and magnitudes of round(2^256 * w_j) for the first row w of
+    /// the inverse of the basis, of which the products with k, shifted by 256 bits, round the
+    /// coordinates of (k, 0, ..., 0).
+    pub(crate) rounding: [(bool, [u64; 3]); D],
+    /// A bound on the bit length of the parts.
+    pub(crate) num_bits: usize,
+}
+
+impl<const D: usize> Decomposition<D> {
+    /// Splits k into (k_0, ..., k_{D-1}), as signs (true for negative) and magnitudes below
+    /// 2^num_bits.
+    pub(crate) fn decompose(&self, k: &Fr) -> [(bool, BigInt<4>); D] {
+        let limbs = k.into_bigint().0;
+        let coords = self.rounding.map(|(neg, g)| {
+            let c = Fr::from_bigint(round_mul_shift(&limbs, &g)).unwrap();
+            if neg {
+                -c
+            } else {
+                c
+            }
+        });
+        let mut parts = [Fr::zero(); D];
+        parts[0] = *k;
+        for (c, row) in coords.iter().zip(&self.basis) {
+            for (part, v) in parts.iter_mut().zip(row) {
+                *part -= *c * v;
+            }
+        }
+        debug_assert_eq!(
+            parts
+                .iter()
+                .rev()
+                .fold(Fr::zero(), |acc, part| acc * self.eigenvalue + part),
+            *k
+        );
+        parts.map(|k| signed(&k))
+    }
+}
+
+/// An endomorphism of a curve with scalar field [`Fr`], which acts on the subgroup of order r as
+/// multiplication by the eigenvalue of [`Self::DECOMPOSITION`].
+pub(crate) trait EndomorphismConfig<const D: usize>:
+    SWCurveConfig<ScalarField = Fr>
+{
+    /// The decomposition of scalars for the eigenvalue of the endomorphism.
+    const DECOMPOSITION: Decomposition<D>;
+
+    /// Applies the endomorphism.
+    fn endomorphism(p: &Projective<Self>) -> Projective<Self>;
+
+    /// Applies the endomorphism to an affine point.
+    fn endomorphism_affine(p: &Affine<Self>) -> Affine<Self>;
+}
+
+/// The GLV endomorphism of a curve y^2 = x^3 + b with scalar field [`Fr`].
+pub(crate) trait GlvConfig: SWCurveConfig<ScalarField = Fr> {
+    /// ENDO_COEFF = beta, the cube root of unity in the base field such that
+    /// (beta * x, y) = [LAMBDA] (x, y) on the subgroup of order r.
+    const ENDO_COEFF: Self::BaseField;
+}
+
+impl<P: GlvConfig> EndomorphismConfig<2> for P {
+    const DECOMPOSITION: Decomposition<2> = GLV_DECOMPOSITION;
+
+    /// Returns phi(P) = (beta * x, y).
+    fn endomorphism(p: &Projective<Self>) -> Projective<Self> {
+        let mut res = *p;
+        res.x *= Self::ENDO_COEFF;
+        res
+    }
+
+    /// Returns phi(P) = (beta * x, y) for an affine point.
+    fn endomorphism_affine(p: &Affine<Self>) -> Affine<Self> {
+        let mut res = *p;
+        res.x *= Self::ENDO_COEFF;
+        res
+    }
+}
+
+/// Multiplies a point of the subgroup of order r by the scalar with the given limbs, with the
+/// decomposition of the endomorphism if the scalar is below r, and with double-and-add otherwise.
+///
+/// The endomorphism only acts as multiplication by its eigenvalue on the subgroup, so for other
+/// points, such as those deserialized without a subgroup check, the result is not the multiple.
+pub(crate) fn mul_projective<P: EndomorphismConfig<D>, const D: usize>(
+    base: &Projective<P>,
+    scalar: &[u64],
+) -> Projective<P> {
+    let k = match scalar_from_limbs(scalar) {
+        Some(k) => k,
+        None => return double_and_add(base, scalar),
+    };
+
+    // The parts multiply the images of the base under the powers of the endomorphism.
+    let mut points = vec![*base];
+    for _ in 1..D {
+        points.push(P::endomorphism(points.last().unwrap()));
+    }
+    linear_combination(&points, &P::DECOMPOSITION.decompose(&k))
+}
+
+/// Computes the sum of the points multiplied by the signed scalars (true for negative), with
+/// shared doublings. This is valid for any points, also outside the subgroup of order r.
+pub(crate) fn linear_combination<P: SWCurveConfig>(
+    points: &[Projective<P>],
+    scalars: &[(bool, BigInt<4>)],
+) -> Projective<P> {
+    // The wNAF digits of the scalars index tables of odd multiples of the points.
+    let parts: Vec<_> = points
+        .iter()
+        .zip(scalars)
+        .map(|(p, (neg, k))| {
+            let p = if *neg { -*p } else { *p };
+            let double = p.double();
+            let mut table = vec![p];
+            for _ in 1..1 << (WNAF_WINDOW - 2) {
+                table.push(*table.last().unwrap() + double);
+            }
+            (k.find_wnaf(WNAF_WINDOW).unwrap(), table)
+        })
+        .collect();
+
+    let len = parts.iter().map(|(wnaf, _)| wnaf.len()).max().unwrap_or(0);
+    let mut res = Projective::<P>::zero();
+    for i in (0..len).rev() {
+        res.double_in_place();
+        for (wnaf, table) in &parts {
+            match wnaf.get(i) {
+                Some(&digit) if digit > 0 => res += table[(digit / 2) as usize],
+                Some(&digit) if digit < 0 => res -= table[(-digit / 2) as usize],
+                _ => {}
+            }
+        }
+    }
+    res
+}
+
+/// Computes the multi-scalar multiplication of points of the subgroup of order r, by splitting
+/// each scalar with the decomposition of the endomorphism and running Pippenger's algorithm on
+/// the parts.
+pub(crate) fn msm<P: EndomorphismConfig<D>, const D: usize>(
+    bases: &[Affine<P>],
+    scalars: &[Fr],
+) -> Result<Projective<P>, usize> {
+    if bases.len() != scalars.len() {
+        return Err(bases.len().min(scalars.len()));
+    }
+
+    let mut split_bases = Vec::with_capacity(D * bases.len());
+    let mut split_scalars = Vec::with_capacity(D * scalars.len());
+    for (base, scalar) in bases.iter().zip(scalars) {
+        let mut p = *base;
+        for (i, (neg, k)) in P::DECOMPOSITION.decompose(scalar).into_iter().enumerate() {
+            if i > 0 {
+                p = P::endomorphism_affine(&p);
+            }
+            split_bases.push(if neg { -p } else { p });
+            split_scalars.push(k);
+        }
+    }
+    Ok(pippenger(
+        &split_bases,
+        &split_scalars,
+        P::DECOMPOSITION.num_bits,
+    ))
+}
+
+/// Returns whether a point of the curve lies in the subgroup of order r, by checking that
+/// v_0 + v_1 * psi + ... + v_{D-1} * psi^(D-1) maps it to zero for the first basis vector v of
+/// the decomposition of the endomorphism psi. As psi acts on the subgroup as multiplication by
+/// the eigenvalue, the subgroup lies in the kernel, which must not contain any other rational
+/// point of the curve, as when this endomorphism has degree r.
+pub(crate) fn is_in_correct_subgroup<P: EndomorphismConfig<D>, const D: usize>(
+    p: &Affine<P>,
+) -> bool {
+    let mut points = vec![(*p).into()];
+    for _ in 1..D {
+        points.push(P::endomorphism(points.last().unwrap()));
+    }
+    let coeffs = P::DECOMPOSITION.basis[0].map(|v| signed(&v));
+    linear_combination(&points, &coeffs).is_zero()
+}
+
+/// Computes the multi-scalar multiplication with scalars below 2^num_bits with the bucket
+/// method, on signed digits in [-2^(c - 1), 2^(c - 1)).
+fn pippenger<P: SWCurveConfig>(
+    bases: &[Affine<P>],
+    scalars: &[BigInt<4>],
+    num_bits: usize,
+) -> Projective<P> {
+    let c = if bases.len() < 32 {
+        3
+    } else {
+        ark_std::log2(bases.len()) as usize * 69 / 100 + 2
+    };
+
+    // One more window takes the carry of the top digit.
+    let num_windows = num_bits.div_ceil(c) + 1;
+    let digits: Vec<Vec<i64>> = cfg_iter!(scalars)
+        .map(|scalar| signed_digits(scalar, c, num_windows))
+        .collect();
+
+    let window_sums: Vec<Projective<P>> = cfg_into_iter!(0..num_windows)
+        .map(|w| {
+            let mut buckets = vec![Projective::<P>::zero(); 1 << (c - 1)];
+            for (base, digits) in bases.iter().zip(&digits) {
+                let digit = digits[w];
+                if digit > 0 {
+                    buckets[(digit - 1) as usize] += base;
+                } else if digit < 0 {
+                    buckets[(-digit - 1) as usize] += -*base;
+                }
+            }
+
+            // The sum of (i + 1) * buckets[i] is the sum of the running sums from the top.
+            let mut running_sum = Projective::<P>::zero();
+            let mut sum = Projective::<P>::zero();
+            for bucket in buckets.into_iter().rev() {
+                running_sum += bucket;
+                sum += running_sum;
+            }
+            sum
+        })
+        .collect();
+
+    let (lowest, rest) = window_sums.split_first().unwrap();
+    let mut res = Projective::<P>::zero();
+    for sum in rest.iter().rev() {
+        res += sum;
+        for _ in 0..c {
+            res.double_in_place();
+        }
+    }
+    res + lowest
+}
+
+/// Returns the digits of the scalar in base 2^c, in [-2^(c - 1), 2^(c - 1)).
+fn signed_digits(scalar: &BigInt<4>, c: usize, num_windows: usize) -> Vec<i64> {
+    let mut carry = 0;
+    (0..num_windows)
+        .map(|w| {
+            let mut digit = window(scalar, w * c, c) as i64 + carry;
+            carry = (digit >= 1 << (c - 1)) as i64;
+            digit -= carry << c;
+            digit
+        })
+        .collect()
+}
+
+/// Returns the c bits of the scalar from bit `start` on.
+fn window(scalar: &BigInt<4>, start: usize, c: usize) -> u64 {
+    let (limb, shift) = (start / 64, start % 64);
+    if limb >= 4 {
+        return 0;
+    }
+    let mut bits = scalar.0[limb] >> shift;
+    if shift + c > 64 && limb + 1 < 4 {
+        bits |= scalar.0[limb + 1] << (64 - shift);
+    }
+    bits & ((1 << c) - 1)
+}
+
+/// Returns round(k * g / 2^256).
+fn round_mul_shift(k: &[u64; 4], g: &[u64; 3]) -> BigInt<4> {
+    let mut t = [0u64; 7];
+    for (i, &k) in k.iter().enumerate() {
+        let mut carry = 0u128;
+        for (j, &g) in g.iter().enumerate() {
+            let s = t[i + j] as u128 + (k as u128) * (g as u128) + carry;
+            t[i + j] = s as u64;
+            carry = s >> 64;
+        }
+        t[i + 3] = carry as u64;
+    }
+
+    // Add 2^255 to round to nearest.
+    let mut carry = 1u128 << 63;
+    for limb in &mut t[3..] {
+        let s = *limb as u128 + carry;
+        *limb = s as u64;
+        carry = s >> 64;
+    }
+    BigInt::new([t[4], t[5], t[6], 0])
+}
+
+/// Returns the sign (true for negative) and magnitude of the representative of k in
+/// (-r / 2, r / 2).
+fn signed(k: &Fr) -> (bool, BigInt<4>) {
+    let bigint = k.into_bigint();
+    if bigint > Fr::MODULUS_MINUS_ONE_DIV_TWO {
+        (true, (-*k).into_bigint())
+    } else {
+        (false, bigint)
+    }
+}
+
+/// Returns the scalar with the given limbs if it is below r.
+fn scalar_from_limbs(limbs: &[u64]) -> Option<Fr> {
+    if limbs.iter().skip(4).any(|&limb| limb != 0) {
+        return None;
+    }
+    let mut bigint = [0u64; 4];
+    for (b, l) in bigint.iter_mut().zip(limbs) {
+        *b = *l;
+    }
+    Fr::from_bigint(BigInt::new(bigint))
+}
+
+fn double_and_add<P: SWCurveConfig>(base: &Projective<P>, scalar: &[u64]) -> Projective<P> {
+    let mut res = Projective::<P>::zero();
+    for b in BitIteratorBE::without_leading_zeros(scalar) {
+        res.double_in_place();
+        if b {
+            res += base;
+        }
+    }
+    res
+}
@@ -0,0 +1,175 @@
+//! Checks of the endomorphism-based arithmetic, generic over the curves, which the tests of each
+//! curve call for its groups.
+
+use crate::{
+    glv::*,
+    yafa_108::{G1Affine, G1Projective},
+};
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::One;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{test_rng, UniformRand};
+
+/// Multiplies the point by the scalar with double-and-add, as a reference.
+pub(crate) fn double_and_add<P: SWCurveConfig>(p: &Projective<P>, scalar: &Fr) -> Projective<P> {
+    let mut res = Projective::<P>::zero();
+    for b in BitIteratorBE::without_leading_zeros(scalar.into_bigint()) {
+        res.double_in_place();
+        if b {
+            res += p;
+        }
+    }
+    res
+}
+
+/// Returns the points of the curve outside the subgroup of order r, with x-coordinates 1, 2, ...
+pub(crate) fn non_subgroup_points<P: SWCurveConfig>() -> impl Iterator<Item = Affine<P>> {
+    (1u64..)
+        .filter_map(|x| Affine::get_point_from_x_unchecked(P::BaseField::from(x), false))
+        .filter(|q| !q.is_in_correct_subgroup_assuming_on_curve())
+}
+
+/// Checks that the endomorphism acts on the subgroup as multiplication by its eigenvalue, on
+/// projective and affine points alike.
+pub(crate) fn check_endomorphism<P: EndomorphismConfig<D>, const D: usize>() {
+    let mut rng = test_rng();
+
+    for _ in 0..5 {
+        let p = Projective::<P>::rand(&mut rng);
+        let image = P::endomorphism(&p);
+        assert_eq!(image, double_and_add(&p, &P::DECOMPOSITION.eigenvalue));
+        assert_eq!(
+            P::endomorphism_affine(&p.into_affine()),
+            image.into_affine()
+        );
+    }
+}
+
+/// Checks that the decomposition splits scalars into parts of at most `num_bits` bits, and that
+/// multiplication agrees with double-and-add.
+pub(crate) fn check_mul<P: EndomorphismConfig<D>, const D: usize>() {
+    let mut rng = test_rng();
+    let p = Projective::<P>::rand(&mut rng);
+    let decomposition = &P::DECOMPOSITION;
+    let mu = decomposition.eigenvalue;
+
+    let mut scalars = vec![Fr::zero(), Fr::one(), -Fr::one(), mu, -mu];
+    scalars.extend((0..10).map(|_| Fr::rand(&mut rng)));
+    for k in scalars {
+        let parts = decomposition.decompose(&k);
+        assert!(parts
+            .iter()
+            .all(|(_, k)| k.num_bits() as usize <= decomposition.num_bits));
+        let recomposed = parts.iter().rev().fold(Fr::zero(), |acc, (neg, k)| {
+            let k = if *neg { -Fr::from(*k) } else { Fr::from(*k) };
+            acc * mu + k
+        });
+        assert_eq!(recomposed, k);
+
+        assert_eq!(p * k, double_and_add(&p, &k));
+    }
+
+    // Scalars of at least r are not reduced, which matters for points outside the subgroup.
+    let q = non_subgroup_points::<P>().next().unwrap();
+    let r = Fr::MODULUS;
+    assert!(!q.mul_bigint(r).is_zero());
+    assert_eq!(Projective::from(q).mul_bigint(r), q.mul_bigint(r));
+
+    // Below r, affine and projective points are multiplied alike, also outside the subgroup.
+    let k = Fr::rand(&mut rng);
+    assert_eq!(q * k, Projective::from(q) * k);
+}
+
+/// Checks the multi-scalar multiplication against double-and-add.
+pub(crate) fn check_msm<P: EndomorphismConfig<D>, const D: usize>() {
+    let mut rng = test_rng();
+
+    for n in [0, 1, 10, 100] {
+        let bases: Vec<Affine<P>> = (0..n)
+            .map(|_| Projective::<P>::rand(&mut rng).into_affine())
+            .collect();
+        let scalars: Vec<Fr> = (0..n).map(|_| Fr::rand(&mut rng)).collect();
+        let expected: Projective<P> = bases
+            .iter()
+            .zip(&scalars)
+            .map(|(p, k)| double_and_add(&(*p).into(), k))
+            .sum();
+        assert_eq!(Projective::<P>::msm(&bases, &scalars).unwrap(), expected);
+    }
+
+    let p = Projective::<P>::rand(&mut rng).into_affine();
+    assert!(Projective::<P>::msm(&[p, p], &[Fr::one()]).is_err());
+}
+
+/// Checks that clearing the cofactor maps points of the curve into the subgroup of order r, and
+/// does not map a random point of the subgroup to zero.
+pub(crate) fn check_clear_cofactor<P: SWCurveConfig>() {
+    let mut rng = test_rng();
+    let p = Projective::<P>::rand(&mut rng).into_affine();
+    assert!(!p.clear_cofactor().is_zero());
+    assert!(Affine::<P>::zero().clear_cofactor().is_zero());
+
+    for q in non_subgroup_points::<P>().take(5) {
+        let q = q.clear_cofactor();
+        assert!(q.is_on_curve() && q.is_in_correct_subgroup_assuming_on_curve());
+        assert!(!q.is_zero());
+    }
+}
+
+/// Checks that the subgroup check accepts the points of the subgroup of order r and rejects the
+/// others, also in checked deserialization.
+pub(crate) fn check_subgroup_check<P: SWCurveConfig>() {
+    let mut rng = test_rng();
+    assert!(Affine::<P>::zero().is_in_correct_subgroup_assuming_on_curve());
+    assert!(Projective::<P>::rand(&mut rng)
+        .into_affine()
+        .is_in_correct_subgroup_assuming_on_curve());
+
+    for q in (1u64..)
+        .filter_map(|x| Affine::<P>::get_point_from_x_unchecked(P::BaseField::from(x), false))
+        .take(10)
+    {
+        let t = q.mul_bigint(Fr::MODULUS).into_affine();
+        assert_eq!(q.is_in_correct_subgroup_assuming_on_curve(), t.is_zero());
+        if t.is_zero() {
+            continue;
+        }
+
+        // Points of order dividing the cofactor are rejected, also after adding a point of the
+        // subgroup.
+        assert!(!t.is_in_correct_subgroup_assuming_on_curve());
+        let p = (Projective::<P>::rand(&mut rng) + t).into_affine();
+        assert!(!p.is_in_correct_subgroup_assuming_on_curve());
+
+        let mut bytes = Vec::new();
+        t.serialize_compressed(&mut bytes).unwrap();
+        assert!(Affine::<P>::deserialize_compressed(&bytes[..]).is_err());
+        assert_eq!(
+            Affine::<P>::deserialize_compressed_unchecked(&bytes[..]).unwrap(),
+            t
+        );
+    }
+}
+
+#[test]
+fn test_pippenger_top_carry() {
+    // With windows of 3 bits, the top digits of 2^65 - 1 and 2^128 - 1 carry into a window of
+    // their own.
+    let mut rng = test_rng();
+    let bases: Vec<G1Affine> = (0..2)
+        .map(|_| G1Projective::rand(&mut rng).into_affine())
+        .collect();
+
+    for num_bits in [65, 128] {
+        let mut limbs = [0u64; 4];
+        for i in 0..num_bits {
+            limbs[i / 64] |= 1 << (i % 64);
+        }
+        let scalar = BigInt::new(limbs);
+        let expected: G1Projective = bases
+            .iter()
+            .map(|p| double_and_add(&(*p).into(), &Fr::from(scalar)))
+            .sum();
+        assert_eq!(pippenger(&bases, &[scalar, scalar], num_bits), expected);
+    }
+}
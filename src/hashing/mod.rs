@@ -0,0 +1,341 @@
+//! Hashing to the Yafa curves and ed25519 following RFC 9380, "Hashing to Elliptic Curves".
+//!
+//! Messages are hashed to field elements with [`XmdFieldHasher`], and mapped to the curve with
+//! [`SvdwMap`], the Shallue--van de Woestijne map, which applies directly to the curves
+//! y^2 = x^3 + b of j-invariant 0, whereas the simplified SWU map needs an isogenous curve with a
+//! nonzero a. With [`MapToCurveBasedHasher`](ark_ec::hashing::map_to_curve_hasher::MapToCurveBasedHasher)
+//! and the effective cofactors of the curves, this gives the hash_to_curve encodings of the
+//! suites of the curves. Twisted Edwards curves such as ed25519 use [`Elligator2Map`] instead,
+//! and the nonuniform encode_to_curve encodings are given by [`MapToCurveBasedEncoder`].
+//!
+//! The field hasher of ark-ff pads the message with as many zero bytes as the length of a field
+//! element rather than the block size of the hash function, which does not follow the RFC.
+use ark_ec::{
+    hashing::{map_to_curve_hasher::MapToCurve, HashToCurve, HashToCurveError},
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    twisted_edwards::{self, MontCurveConfig, TECurveConfig},
+    AffineRepr, CurveGroup,
+};
+use ark_ff::{field_hashers::HashToField, BigInteger, Field, PrimeField, Zero};
+use ark_std::{marker::PhantomData, string::ToString, vec, vec::Vec};
+use digest::{core_api::BlockSizeUser, Digest};
+
+/// Returns `len_in_bytes` uniform bytes of expand_message_xmd with the hash function H, as in
+/// RFC 9380, Section 5.3.1, or `None` if more than 255 blocks or 65535 bytes are requested.
+///
+/// DSTs longer than 255 bytes are replaced by their hash, as in Section 5.3.3.
+pub fn expand_message_xmd<H: Digest + BlockSizeUser>(
+    msg: &[u8],
+    dst: &[u8],
+    len_in_bytes: usize,
+) -> Option<Vec<u8>> {
+    let ell = len_in_bytes.div_ceil(<H as Digest>::output_size());
+    if ell > 255 || len_in_bytes > 65535 {
+        return None;
+    }
+
+    let long_dst;
+    let dst = if dst.len() > 255 {
+        long_dst = H::new()
+            .chain_update(b"H2C-OVERSIZE-DST-")
+            .chain_update(dst)
+            .finalize();
+        &long_dst[..]
+    } else {
+        dst
+    };
+    let dst_len = [dst.len() as u8];
+
+    let b_0 = H::new()
+        .chain_update(vec![0u8; H::block_size()])
+        .chain_update(msg)
+        .chain_update((len_in_bytes as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(dst)
+        .chain_update(dst_len)
+        .finalize();
+    let mut b_i = H::new()
+        .chain_update(&b_0)
+        .chain_update([1u8])
+        .chain_update(dst)
+        .chain_update(dst_len)
+        .finalize();
+
+    let mut uniform_bytes = b_i.to_vec();
+    for i in 2..=ell {
+        let xor: Vec<u8> = b_0.iter().zip(&b_i).map(|(a, b)| a ^ b).collect();
+        b_i = H::new()
+            .chain_update(xor)
+            .chain_update([i as u8])
+            .chain_update(dst)
+            .chain_update(dst_len)
+            .finalize();
+        uniform_bytes.extend_from_slice(&b_i);
+    }
+    uniform_bytes.truncate(len_in_bytes);
+    Some(uniform_bytes)
+}
+
+/// hash_to_field of RFC 9380, Section 5.2, with [`expand_message_xmd`] and the hash function H,
+/// for the security level of SEC_PARAM bits.
+pub struct XmdFieldHasher<H, const SEC_PARAM: usize = 128> {
+    dst: Vec<u8>,
+    _hash: PhantomData<H>,
+}
+
+impl<F: Field, H: Digest + BlockSizeUser, const SEC_PARAM: usize> HashToField<F>
+    for XmdFieldHasher<H, SEC_PARAM>
+{
+    fn new(dst: &[u8]) -> Self {
+        XmdFieldHasher {
+            dst: dst.to_vec(),
+            _hash: PhantomData,
+        }
+    }
+
+    /// Hashes to `count` elements of F, each of `F::extension_degree()` coordinates of
+    /// L = ceil((ceil(log2(p)) + SEC_PARAM) / 8) bytes reduced mod p.
+    ///
+    /// Panics if this needs more bytes than [`expand_message_xmd`] produces.
+    fn hash_to_field(&self, msg: &[u8], count: usize) -> Vec<F> {
+        let m = F::extension_degree() as usize;
+        let len = (F::BasePrimeField::MODULUS_BIT_SIZE as usize + SEC_PARAM).div_ceil(8);
+        let uniform_bytes = expand_message_xmd::<H>(msg, &self.dst, count * m * len)
+            .expect("too many bytes for expand_message_xmd");
+
+        uniform_bytes
+            .chunks(m * len)
+            .map(|bytes| {
+                let coeffs: Vec<F::BasePrimeField> = bytes
+                    .chunks(len)
+                    .map(F::BasePrimeField::from_be_bytes_mod_order)
+                    .collect();
+                F::from_base_prime_field_elems(&coeffs).unwrap()
+            })
+            .collect()
+    }
+}
+
+/// The parameters of the Shallue--van de Woestijne map of a curve.
+pub trait SvdwConfig: SWCurveConfig {
+    /// The constant Z of the map, as found by find_z_svdw of RFC 9380, Appendix H.1.
+    const Z: Self::BaseField;
+
+    /// Computes a square root of `fe`, or returns `None` if `fe` is not a square.
+    fn sqrt(fe: &Self::BaseField) -> Option<Self::BaseField>;
+}
+
+/// The Shallue--van de Woestijne map of RFC 9380, Section 6.6.1, with its constants
+/// c1 = g(Z), c2 = -Z / 2, c3 = sqrt(-g(Z) * (3 * Z^2 + 4 * A)) with sgn0(c3) = 0, and
+/// c4 = -4 * g(Z) / (3 * Z^2 + 4 * A), where g(x) = x^3 + A * x + B.
+///
+/// The map branches on whether g(x1) and g(x2) are squares, so it is not constant-time.
+pub struct SvdwMap<P: SvdwConfig> {
+    c1: P::BaseField,
+    c2: P::BaseField,
+    c3: P::BaseField,
+    c4: P::BaseField,
+}
+
+impl<P: SvdwConfig> MapToCurve<Projective<P>> for SvdwMap<P> {
+    /// Computes the constants, or fails if Z does not meet the criteria of find_z_svdw.
+    fn new() -> Result<Self, HashToCurveError> {
+        let invalid = || HashToCurveError::MapToCurveError("invalid SvdW constant Z".to_string());
+        let z = P::Z;
+        let gz = g::<P>(&z);
+        let t = z.square() * P::BaseField::from(3u8) + P::COEFF_A.double().double();
+        let c2 = -z / P::BaseField::from(2u8);
+        if gz.is_zero()
+            || t.is_zero()
+            || (P::sqrt(&gz).is_none() && P::sqrt(&g::<P>(&c2)).is_none())
+        {
+            return Err(invalid());
+        }
+
+        // -t / (4 * g(Z)) is a square exactly when -g(Z) * t is.
+        let mut c3 = P::sqrt(&-(gz * t)).ok_or_else(invalid)?;
+        if sgn0(&c3) {
+            c3 = -c3;
+        }
+        let c4 = -gz.double().double() / t;
+        Ok(SvdwMap { c1: gz, c2, c3, c4 })
+    }
+
+    fn map_to_curve(&self, u: P::BaseField) -> Result<Affine<P>, HashToCurveError> {
+        let one = P::BaseField::ONE;
+        let tv1 = u.square() * self.c1;
+        let tv2 = one + tv1;
+        let tv1 = one - tv1;
+        let tv3 = (tv1 * tv2).inverse().unwrap_or_default();
+        let tv4 = u * tv1 * tv3 * self.c3;
+        let x1 = self.c2 - tv4;
+        let x2 = self.c2 + tv4;
+        let x3 = (tv2.square() * tv3).square() * self.c4 + P::Z;
+
+        // g(x3) is a square when neither g(x1) nor g(x2) is.
+        let (x, y) = [x1, x2, x3]
+            .into_iter()
+            .find_map(|x| P::sqrt(&g::<P>(&x)).map(|y| (x, y)))
+            .ok_or_else(|| {
+                HashToCurveError::MapToCurveError("no square among g(x1), g(x2), g(x3)".to_string())
+            })?;
+        let y = if sgn0(&u) == sgn0(&y) { y } else { -y };
+        Ok(Affine::new_unchecked(x, y))
+    }
+}
+
+/// The parameters of the Elligator 2 map of a twisted Edwards curve.
+pub trait Elligator2Config: TECurveConfig {
+    /// The non-square constant Z of the map, as found by find_z_ell2 of RFC 9380, Appendix H.2.
+    const Z: Self::BaseField;
+}
+
+/// The Elligator 2 map of RFC 9380, Section 6.7.1, to the Montgomery curve
+/// t^2 = s^3 + A * s^2 + s, followed by the rational map
+/// (s, t) -> (c1 * s / t, (s - 1) / (s + 1)) to the twisted Edwards curve of Appendix D.1. Here
+/// A and B are the coefficients of the Montgomery form B * v^2 = u^3 + A * u^2 + u of the
+/// [`MontCurveConfig`] of the curve, and c1 = sqrt(B) with sgn0(c1) = 0, so that t = c1 * v.
+/// For edwards25519 this is map_to_curve_elligator2_edwards25519 of Appendix G.2.2.
+///
+/// The map branches on whether g(x1) is a square, so it is not constant-time.
+pub struct Elligator2Map<P: Elligator2Config> {
+    c1: P::BaseField,
+}
+
+impl<P: Elligator2Config> MapToCurve<twisted_edwards::Projective<P>> for Elligator2Map<P> {
+    /// Computes the constant, or fails if Z is a square or B is not.
+    fn new() -> Result<Self, HashToCurveError> {
+        if P::Z.sqrt().is_some() {
+            return Err(HashToCurveError::MapToCurveError(
+                "invalid Elligator 2 constant Z".to_string(),
+            ));
+        }
+        let mut c1 = <P::MontCurveConfig as MontCurveConfig>::COEFF_B
+            .sqrt()
+            .ok_or_else(|| {
+                HashToCurveError::MapToCurveError(
+                    "the Montgomery coefficient B is not a square".to_string(),
+                )
+            })?;
+        if sgn0(&c1) {
+            c1 = -c1;
+        }
+        Ok(Elligator2Map { c1 })
+    }
+
+    fn map_to_curve(
+        &self,
+        u: P::BaseField,
+    ) -> Result<twisted_edwards::Affine<P>, HashToCurveError> {
+        let one = P::BaseField::ONE;
+        let a = <P::MontCurveConfig as MontCurveConfig>::COEFF_A;
+        let mut x1 = -a * (P::Z * u.square() + one).inverse().unwrap_or_default();
+        if x1.is_zero() {
+            x1 = -a;
+        }
+
+        // g(x2) = Z * u^2 * g(x1) is a square when g(x1) is not.
+        let (s, t) = match g_mont::<P>(&x1).sqrt() {
+            Some(y) => (x1, if sgn0(&y) { y } else { -y }),
+            None => {
+                let x2 = -x1 - a;
+                let y = g_mont::<P>(&x2).sqrt().ok_or_else(|| {
+                    HashToCurveError::MapToCurveError("no square among g(x1), g(x2)".to_string())
+                })?;
+                (x2, if sgn0(&y) { -y } else { y })
+            }
+        };
+
+        // The exceptional points t = 0 and s = -1 go to the identity.
+        match (t * (s + one)).inverse() {
+            Some(inv) => Ok(twisted_edwards::Affine::new_unchecked(
+                self.c1 * s * (s + one) * inv,
+                (s - one) * t * inv,
+            )),
+            None => Ok(twisted_edwards::Affine::zero()),
+        }
+    }
+}
+
+impl<P: Elligator2Config> Elligator2Map<P> {
+    /// Returns the preimage u of `p` under the map with sgn0(u) = 0, the other one being -u, or
+    /// `None` if `p` is not in the image of the map, which is about half of the curve.
+    ///
+    /// This is the inverse map of Elligator 2 of Bernstein, Hamburg, Krasnova and Lange, which
+    /// encodes the points of the image as field elements that, for random points, are
+    /// indistinguishable from uniformly random ones up to the sign.
+    pub fn inverse_map(&self, p: &twisted_edwards::Affine<P>) -> Option<P::BaseField> {
+        // This fails for the identity, which the map only reaches in its exceptional cases, and
+        // for the point (0, -1) of order 2, which it does not reach.
+        let one = P::BaseField::ONE;
+        let s = (one + p.y) * (one - p.y).inverse()?;
+        let t = self.c1 * s * p.x.inverse()?;
+
+        // sgn0(t) = 1 for s = x1 = -A / (1 + Z * u^2), and sgn0(t) = 0 for s = x2 = -x1 - A.
+        let a = <P::MontCurveConfig as MontCurveConfig>::COEFF_A;
+        let u2 = if sgn0(&t) {
+            -(s + a) * (P::Z * s).inverse()?
+        } else {
+            -s * (P::Z * (s + a)).inverse()?
+        };
+        let u = u2.sqrt()?;
+        Some(if sgn0(&u) { -u } else { u })
+    }
+}
+
+/// The encode_to_curve encoding of RFC 9380, Section 3, of the nonuniform suites, which maps a
+/// single field element to the curve and clears the cofactor. This is cheaper than
+/// [`MapToCurveBasedHasher`](ark_ec::hashing::map_to_curve_hasher::MapToCurveBasedHasher), but
+/// its output is not uniformly distributed, so it does not replace a random oracle.
+pub struct MapToCurveBasedEncoder<T, H2F, M2C>
+where
+    T: CurveGroup,
+    H2F: HashToField<T::BaseField>,
+    M2C: MapToCurve<T>,
+{
+    field_hasher: H2F,
+    curve_mapper: M2C,
+    _params: PhantomData<T>,
+}
+
+impl<T, H2F, M2C> HashToCurve<T> for MapToCurveBasedEncoder<T, H2F, M2C>
+where
+    T: CurveGroup,
+    H2F: HashToField<T::BaseField>,
+    M2C: MapToCurve<T>,
+{
+    fn new(domain: &[u8]) -> Result<Self, HashToCurveError> {
+        Ok(MapToCurveBasedEncoder {
+            field_hasher: H2F::new(domain),
+            curve_mapper: M2C::new()?,
+            _params: PhantomData,
+        })
+    }
+
+    fn hash(&self, msg: &[u8]) -> Result<T::Affine, HashToCurveError> {
+        let u = self.field_hasher.hash_to_field(msg, 1);
+        Ok(self.curve_mapper.map_to_curve(u[0])?.clear_cofactor())
+    }
+}
+
+/// Returns g(x) = x^3 + A * x + B.
+fn g<P: SWCurveConfig>(x: &P::BaseField) -> P::BaseField {
+    (x.square() + P::COEFF_A) * x + P::COEFF_B
+}
+
+/// Returns s^3 + A * s^2 + s for the coefficient A of the Montgomery form.
+fn g_mont<P: TECurveConfig>(s: &P::BaseField) -> P::BaseField {
+    let a = <P::MontCurveConfig as MontCurveConfig>::COEFF_A;
+    ((*s + a) * s + P::BaseField::ONE) * s
+}
+
+/// sgn0 of RFC 9380, Section 4.1: the parity of the first nonzero coordinate over the prime
+/// field.
+pub(crate) fn sgn0<F: Field>(fe: &F) -> bool {
+    fe.to_base_prime_field_elements()
+        .find(|c| !c.is_zero())
+        .is_some_and(|c| c.into_bigint().is_odd())
+}
+
+#[cfg(test)]
+pub(crate) mod tests;
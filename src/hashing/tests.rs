@@ -0,0 +1,206 @@
+use crate::{
+    ed25519::{EdwardsAffine, EdwardsParameters, EdwardsProjective},
+    hashing::*,
+    yafa_108::{g1, Fq, G1Affine},
+};
+use ark_ec::{
+    hashing::{
+        map_to_curve_hasher::{MapToCurve, MapToCurveBasedHasher},
+        HashToCurve,
+    },
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    AffineRepr, CurveGroup,
+};
+use ark_ff::{field_hashers::HashToField, Field, One, PrimeField, Zero};
+use ark_std::{str::FromStr, test_rng, vec, vec::Vec, UniformRand};
+use digest::{core_api::BlockSizeUser, Digest};
+use sha2::{Sha256, Sha512};
+
+fn from_hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+/// The messages of the test vectors of RFC 9380, Appendix J.
+pub(crate) fn hash_to_curve_messages() -> [Vec<u8>; 5] {
+    [
+        b"".to_vec(),
+        b"abc".to_vec(),
+        b"abcdef0123456789".to_vec(),
+        [&b"q128_"[..], &[b'q'; 128]].concat(),
+        [&b"a512_"[..], &[b'a'; 512]].concat(),
+    ]
+}
+
+/// Checks the field elements u0, u1 and the point P hashed from the messages with the DST
+/// QUUX-V01-CS02-with- followed by the suite ID, as in RFC 9380, Appendix J. Each vector lists
+/// u0, u1 and the coordinates of P by their coefficients over the prime field, in decimal.
+pub(crate) fn check_hash_to_curve<P: SvdwConfig, H: Digest + BlockSizeUser, const N: usize>(
+    suite_id: &[u8],
+    vectors: &[[&str; N]],
+) {
+    let dst = [&b"QUUX-V01-CS02-with-"[..], suite_id].concat();
+    let field_hasher = <XmdFieldHasher<H> as HashToField<P::BaseField>>::new(&dst);
+    let hasher =
+        MapToCurveBasedHasher::<Projective<P>, XmdFieldHasher<H>, SvdwMap<P>>::new(&dst).unwrap();
+
+    for (msg, vector) in hash_to_curve_messages().iter().zip(vectors) {
+        let coeffs: Vec<_> = vector
+            .iter()
+            .map(|s| {
+                <P::BaseField as Field>::BasePrimeField::from_str(s)
+                    .ok()
+                    .unwrap()
+            })
+            .collect();
+        let [u0, u1, x, y] = [0, 1, 2, 3].map(|i| {
+            P::BaseField::from_base_prime_field_elems(&coeffs[i * N / 4..(i + 1) * N / 4]).unwrap()
+        });
+        let u: Vec<P::BaseField> = field_hasher.hash_to_field(msg, 2);
+        assert_eq!(u, vec![u0, u1]);
+        let p = hasher.hash(msg).unwrap();
+        assert_eq!(p, Affine::new(x, y));
+        assert!(p.is_in_correct_subgroup_assuming_on_curve());
+    }
+}
+
+#[test]
+fn test_expand_message_xmd() {
+    // The test vectors of RFC 9380, Appendix K.1 and K.3.
+    let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+    for (msg, len, expected) in [
+        ("", 0x20, "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"),
+        ("abc", 0x20, "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"),
+        ("", 0x80, "af84c27ccfd45d41914fdff5df25293e221afc53d8ad2ac06d5e3e29485dadbee0d121587713a3e0dd4d5e69e93eb7cd4f5df4cd103e188cf60cb02edc3edf18eda8576c412b18ffb658e3dd6ec849469b979d444cf7b26911a08e63cf31f9dcc541708d3491184472c2c29bb749d4286b004ceb5ee6b9a7fa5b646c993f0ced"),
+    ] {
+        assert_eq!(
+            expand_message_xmd::<Sha256>(msg.as_bytes(), dst, len).unwrap(),
+            from_hex(expected)
+        );
+    }
+    let msg = [&b"q128_"[..], &[b'q'; 128]].concat();
+    assert_eq!(
+        expand_message_xmd::<Sha256>(&msg, dst, 0x80).unwrap(),
+        from_hex("80be107d0884f0d881bb460322f0443d38bd222db8bd0b0a5312a6fedb49c1bbd88fd75d8b9a09486c60123dfa1d73c1cc3169761b17476d3c6b7cbbd727acd0e2c942f4dd96ae3da5de368d26b32286e32de7e5a8cb2949f866a0b80c58116b29fa7fabb3ea7d520ee603e0c25bcaf0b9a5e92ec6a1fe4e0391d1cdbce8c68a")
+    );
+
+    let dst = b"QUUX-V01-CS02-with-expander-SHA512-256";
+    assert_eq!(
+        expand_message_xmd::<Sha512>(b"", dst, 0x20).unwrap(),
+        from_hex("6b9a7312411d92f921c6f68ca0b6380730a1a4d982c507211a90964c394179ba")
+    );
+    assert_eq!(
+        expand_message_xmd::<Sha512>(b"abcdef0123456789", dst, 0x80).unwrap(),
+        from_hex("3f721f208e6199fe903545abc26c837ce59ac6fa45733f1baaf0222f8b7acb0424814fcb5eecf6c1d38f06e9d0a6ccfbf85ae612ab8735dfdf9ce84c372a77c8f9e1c1e952c3a61b7567dd0693016af51d2745822663d0c2367e3f4f0bed827feecc2aaf98c949b5ed0d35c3f1023d64ad1407924288d366ea159f46287e61ac")
+    );
+
+    // A DST longer than 255 bytes is hashed first.
+    let dst = [
+        &b"QUUX-V01-CS02-with-expander-SHA256-128-long-DST-"[..],
+        &[b'1'; 208],
+    ]
+    .concat();
+    assert_eq!(dst.len(), 256);
+    assert_eq!(
+        expand_message_xmd::<Sha256>(b"", &dst, 0x20).unwrap(),
+        from_hex("e8dc0c8b686b7ef2074086fbdd2f30e3f8bfbd3bdf177f73f04b97ce618a3ed3")
+    );
+    assert_eq!(
+        expand_message_xmd::<Sha256>(b"abc", &dst, 0x80).unwrap(),
+        from_hex("1a30a5e36fbdb87077552b9d18b9f0aee16e80181d5b951d0471d55b66684914aef87dbb3626eaabf5ded8cd0686567e503853e5c84c259ba0efc37f71c839da2129fe81afdaec7fbdc0ccd4c794727a17c0d20ff0ea55e1389d6982d1241cb8d165762dbc39fb0cee4474d2cbbd468a835ae5b2f20e4f959f56ab24cd6fe267")
+    );
+
+    assert_eq!(
+        expand_message_xmd::<Sha256>(b"", b"DST", 255 * 32).map(|b| b.len()),
+        Some(255 * 32)
+    );
+    assert!(expand_message_xmd::<Sha256>(b"", b"DST", 255 * 32 + 1).is_none());
+    assert!(expand_message_xmd::<Sha512>(b"", b"DST", 65536).is_none());
+}
+
+#[test]
+fn test_hash_to_field() {
+    let hasher = <XmdFieldHasher<Sha256> as HashToField<Fq>>::new(b"DST");
+    let elems: Vec<Fq> = hasher.hash_to_field(b"msg", 3);
+    assert_eq!(elems.len(), 3);
+    let bytes = expand_message_xmd::<Sha256>(b"msg", b"DST", 3 * 88).unwrap();
+    for (e, chunk) in elems.iter().zip(bytes.chunks(88)) {
+        assert_eq!(*e, Fq::from_be_bytes_mod_order(chunk));
+    }
+    // The length of the expanded message is part of it, so the elements depend on the count.
+    assert_ne!(elems[..2], hasher.hash_to_field(b"msg", 2)[..]);
+    assert_ne!(elems[0], elems[1]);
+    assert_ne!(elems, hasher.hash_to_field(b"msh", 3));
+
+    let other = <XmdFieldHasher<Sha256> as HashToField<Fq>>::new(b"DSU");
+    assert_ne!(elems, other.hash_to_field(b"msg", 3));
+}
+
+#[test]
+fn test_sgn0() {
+    assert!(!sgn0(&Fq::zero()));
+    assert!(sgn0(&Fq::one()));
+    assert!(!sgn0(&-Fq::one()));
+    assert!(!sgn0(&Fq::from(2u8)));
+}
+
+#[test]
+fn test_svdw_map() {
+    let mut rng = test_rng();
+    let map = <SvdwMap<g1::Parameters> as MapToCurve<_>>::new().unwrap();
+
+    // The exceptional cases u = 0 and u^2 * g(Z) = -1, 1, where tv1 * tv2 = 0.
+    let gz =
+        <g1::Parameters as SvdwConfig>::Z.pow([3]) + <g1::Parameters as SWCurveConfig>::COEFF_B;
+    let mut inputs = vec![Fq::zero()];
+    for t in [gz.inverse().unwrap(), -gz.inverse().unwrap()] {
+        inputs.extend(t.sqrt());
+    }
+    inputs.extend((0..20).map(|_| Fq::rand(&mut rng)));
+
+    for u in inputs {
+        let p: G1Affine = map.map_to_curve(u).unwrap();
+        assert!(p.is_on_curve());
+        assert!(!p.is_zero());
+        assert_eq!(sgn0(&p.y), sgn0(&u));
+    }
+}
+
+#[test]
+fn test_elligator2_map() {
+    type Fq25519 = crate::ed25519::Fq;
+    let mut rng = test_rng();
+    let map = <Elligator2Map<EdwardsParameters> as MapToCurve<EdwardsProjective>>::new().unwrap();
+
+    for _ in 0..20 {
+        let u = Fq25519::rand(&mut rng);
+        let p = map.map_to_curve(u).unwrap();
+        assert!(p.is_on_curve());
+        assert_eq!(map.map_to_curve(-u).unwrap(), p);
+
+        let v = map.inverse_map(&p).unwrap();
+        assert!(v == u || v == -u);
+        assert!(!sgn0(&v));
+    }
+    assert!(map.map_to_curve(Fq25519::zero()).unwrap().is_on_curve());
+
+    // About half of the points have a preimage, which maps back to the point.
+    let mut count = 0;
+    for _ in 0..100 {
+        let p = EdwardsProjective::rand(&mut rng).into_affine();
+        if let Some(u) = map.inverse_map(&p) {
+            assert_eq!(map.map_to_curve(u).unwrap(), p);
+            count += 1;
+        }
+    }
+    assert!((25..75).contains(&count));
+    assert!(map.inverse_map(&EdwardsAffine::zero()).is_none());
+    assert!(map
+        .inverse_map(&EdwardsAffine::new_unchecked(
+            Fq25519::zero(),
+            -Fq25519::one()
+        ))
+        .is_none());
+}
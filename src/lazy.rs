@@ -0,0 +1,263 @@
+//! Unreduced double-width products in the 576-bit base fields of the Yafa curves, for lazy
+//! reduction in the extension field towers.
+//!
+//! An extension field product is a sum of base field products. Adding up the double-width
+//! products and reducing each coefficient once saves most of the Montgomery reductions, which
+//! cost about as much as the products themselves.
+
+use ark_ff::{
+    biginteger::BigInt,
+    fields::{Fp576, MontBackend, MontConfig},
+};
+use ark_std::{
+    marker::PhantomData,
+    ops::{Add, Sub},
+};
+
+type Fq<P> = Fp576<MontBackend<P, 9>>;
+
+/// An integer t = hi * 2^576 + lo with hi < q, which stands for the element t / 2^576 of Fq.
+///
+/// Since elements of Fq are in Montgomery form with R = 2^576, the product of a * R and b * R
+/// stands for a * b * R, and [`Self::reduce`] turns it into the Montgomery form of a * b.
+pub(crate) struct FqWide<P: MontConfig<9>> {
+    lo: [u64; 9],
+    hi: [u64; 9],
+    config: PhantomData<P>,
+}
+
+impl<P: MontConfig<9>> Clone for FqWide<P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P: MontConfig<9>> Copy for FqWide<P> {}
+
+impl<P: MontConfig<9>> FqWide<P> {
+    /// floor(2^125 / (q_8 + 1)), where q_8 is the top limb of q. This fits in a u64 since
+    /// q >= 2^573, so q_8 >= 2^61.
+    const RECIPROCAL: u64 = ((1u128 << 125) / (P::MODULUS.0[8] as u128 + 1)) as u64;
+
+    fn new(lo: [u64; 9], hi: [u64; 9]) -> Self {
+        Self {
+            lo,
+            hi,
+            config: PhantomData,
+        }
+    }
+
+    /// Returns the double-width product of a and b, which is below q^2 < q * 2^576.
+    #[inline]
+    pub(crate) fn mul(a: &Fq<P>, b: &Fq<P>) -> Self {
+        let (a, b) = (&(a.0).0, &(b.0).0);
+        let mut t = [0u64; 18];
+        for i in 0..9 {
+            let mut carry = 0;
+            for j in 0..9 {
+                t[i + j] = mac(t[i + j], a[i], b[j], &mut carry);
+            }
+            t[i + 9] = carry;
+        }
+
+        let mut lo = [0u64; 9];
+        let mut hi = [0u64; 9];
+        lo.copy_from_slice(&t[..9]);
+        hi.copy_from_slice(&t[9..]);
+        Self::new(lo, hi)
+    }
+
+    /// Returns the double-width product of a and the small integer k < 2^32, with the high
+    /// half reduced modulo q.
+    #[inline]
+    pub(crate) fn mul_by_u64(&self, k: u64) -> Self {
+        debug_assert!(k < 1 << 32);
+        let mut carry = 0;
+        let lo = self.lo.map(|limb| mac(0, limb, k, &mut carry));
+
+        // The high half is now h = k * hi + carry < (k + 1) * q, in ten limbs.
+        let mut h = [0u64; 10];
+        for (h, &limb) in h.iter_mut().zip(&self.hi) {
+            *h = mac(0, limb, k, &mut carry);
+        }
+        h[9] = carry;
+
+        // Subtract an estimate e of h / q. With x = floor(h / 2^512) < 2^96, this is
+        // e = floor(x * RECIPROCAL / 2^125) <= x / (q_8 + 1) <= h / q, where q_8 >= 2^61 is
+        // the top limb of q, and e is short of floor(h / q) by at most 2.
+        let modulus = &P::MODULUS.0;
+        let reciprocal = Self::RECIPROCAL as u128;
+        let estimate = (h[9] as u128) * reciprocal + (((h[8] as u128) * reciprocal) >> 64);
+        let quotient = (estimate >> 61) as u64;
+        let mut mul_carry = 0;
+        let mut borrow = 0;
+        for i in 0..9 {
+            let product = mac(0, modulus[i], quotient, &mut mul_carry);
+            h[i] = sbb(h[i], product, &mut borrow);
+        }
+        h[9] = h[9].wrapping_sub(mul_carry).wrapping_sub(borrow);
+
+        // Now h < 3 * q, and two conditional subtractions without branches reduce it below q.
+        for _ in 0..2 {
+            let mut d = [0u64; 10];
+            let mut borrow = 0;
+            for i in 0..9 {
+                d[i] = sbb(h[i], modulus[i], &mut borrow);
+            }
+            d[9] = sbb(h[9], 0, &mut borrow);
+            let keep = borrow.wrapping_neg();
+            for i in 0..10 {
+                h[i] = (h[i] & keep) | (d[i] & !keep);
+            }
+        }
+
+        let mut hi = [0u64; 9];
+        hi.copy_from_slice(&h[..9]);
+        Self::new(lo, hi)
+    }
+
+    /// Returns the element t / 2^576 of Fq, with a single Montgomery reduction.
+    #[inline]
+    pub(crate) fn reduce(&self) -> Fq<P> {
+        let modulus = &P::MODULUS.0;
+        let mut t = [0u64; 18];
+        t[..9].copy_from_slice(&self.lo);
+        t[9..].copy_from_slice(&self.hi);
+
+        let mut carry2 = 0;
+        for i in 0..9 {
+            let k = t[i].wrapping_mul(P::INV);
+            let mut carry = 0;
+            mac(t[i], k, modulus[0], &mut carry);
+            for j in 1..9 {
+                t[i + j] = mac(t[i + j], k, modulus[j], &mut carry);
+            }
+            t[i + 9] = adc(t[i + 9], carry, &mut carry2);
+        }
+
+        // The result (t + m * q) / 2^576 is below 2 * q, since t < q * 2^576.
+        let mut r = [0u64; 9];
+        r.copy_from_slice(&t[9..]);
+        if geq(&r, modulus) {
+            let mut borrow = 0;
+            for i in 0..9 {
+                r[i] = sbb(r[i], modulus[i], &mut borrow);
+            }
+        }
+        Fq::<P>::new_unchecked(BigInt::new(r))
+    }
+}
+
+impl<P: MontConfig<9>> Add for FqWide<P> {
+    type Output = Self;
+
+    /// Adds the integers, and subtracts q * 2^576 if the high half reaches q.
+    #[inline]
+    fn add(mut self, other: Self) -> Self {
+        let mut carry = 0;
+        for i in 0..9 {
+            self.lo[i] = adc(self.lo[i], other.lo[i], &mut carry);
+        }
+        for i in 0..9 {
+            self.hi[i] = adc(self.hi[i], other.hi[i], &mut carry);
+        }
+
+        let modulus = &P::MODULUS.0;
+        if geq(&self.hi, modulus) {
+            let mut borrow = 0;
+            for (limb, &q) in self.hi.iter_mut().zip(modulus) {
+                *limb = sbb(*limb, q, &mut borrow);
+            }
+        }
+        self
+    }
+}
+
+impl<P: MontConfig<9>> Sub for FqWide<P> {
+    type Output = Self;
+
+    /// Subtracts the integers, and adds q * 2^576 if the difference is negative.
+    #[inline]
+    fn sub(mut self, other: Self) -> Self {
+        let mut borrow = 0;
+        for i in 0..9 {
+            self.lo[i] = sbb(self.lo[i], other.lo[i], &mut borrow);
+        }
+        for i in 0..9 {
+            self.hi[i] = sbb(self.hi[i], other.hi[i], &mut borrow);
+        }
+
+        if borrow != 0 {
+            let mut carry = 0;
+            for (limb, &q) in self.hi.iter_mut().zip(&P::MODULUS.0) {
+                *limb = adc(*limb, q, &mut carry);
+            }
+        }
+        self
+    }
+}
+
+/// Adds unreduced elements of an extension field, coefficient by coefficient.
+#[inline]
+pub(crate) fn add<P: MontConfig<9>, const N: usize>(
+    mut a: [FqWide<P>; N],
+    b: [FqWide<P>; N],
+) -> [FqWide<P>; N] {
+    for (a, b) in a.iter_mut().zip(b) {
+        *a = *a + b;
+    }
+    a
+}
+
+/// Subtracts unreduced elements of an extension field, coefficient by coefficient.
+#[inline]
+pub(crate) fn sub<P: MontConfig<9>, const N: usize>(
+    mut a: [FqWide<P>; N],
+    b: [FqWide<P>; N],
+) -> [FqWide<P>; N] {
+    for (a, b) in a.iter_mut().zip(b) {
+        *a = *a - b;
+    }
+    a
+}
+
+/// Reduces each coefficient of an unreduced element of an extension field.
+#[inline]
+pub(crate) fn reduce<P: MontConfig<9>, const N: usize>(a: &[FqWide<P>; N]) -> [Fq<P>; N] {
+    a.map(|a| a.reduce())
+}
+
+/// Returns a + b * c + carry, and sets carry to the high limb.
+#[inline(always)]
+fn mac(a: u64, b: u64, c: u64, carry: &mut u64) -> u64 {
+    let t = (a as u128) + (b as u128) * (c as u128) + (*carry as u128);
+    *carry = (t >> 64) as u64;
+    t as u64
+}
+
+/// Returns a + b + carry, and sets carry to the high limb.
+#[inline(always)]
+fn adc(a: u64, b: u64, carry: &mut u64) -> u64 {
+    let t = (a as u128) + (b as u128) + (*carry as u128);
+    *carry = (t >> 64) as u64;
+    t as u64
+}
+
+/// Returns a - b - borrow, and sets borrow to 1 if this wraps around and to 0 otherwise.
+#[inline(always)]
+fn sbb(a: u64, b: u64, borrow: &mut u64) -> u64 {
+    let t = (a as u128).wrapping_sub((b as u128) + (*borrow as u128));
+    *borrow = (t >> 127) as u64;
+    t as u64
+}
+
+/// Returns whether a >= b.
+#[inline(always)]
+fn geq(a: &[u64; 9], b: &[u64; 9]) -> bool {
+    for i in (0..9).rev() {
+        if a[i] != b[i] {
+            return a[i] > b[i];
+        }
+    }
+    true
+}
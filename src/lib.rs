@@ -9,5 +9,12 @@
 #![forbid(unsafe_code)]
 
 pub mod ed25519;
+pub mod hashing;
 pub mod yafa_108;
 pub mod yafa_146;
+
+mod cyclotomic;
+mod glv;
+mod lazy;
+mod safegcd;
+mod serialization;
@@ -0,0 +1,239 @@
+//! Constant-time modular inversion for the 576-bit base fields of the Yafa curves, with the
+//! divsteps of Bernstein and Yang, "Fast constant-time gcd computation and modular inversion".
+//!
+//! This follows the constant-time variant in libsecp256k1 (`modinv64`), with integers in signed
+//! base 2^62 and batches of 62 divsteps, of which the transition matrices are applied to the
+//! full-size integers at once.
+
+use ark_ff::BigInt;
+
+/// The number of limbs of 62 bits, enough for integers in (-2^578, 2^578).
+const LIMBS: usize = 10;
+
+/// The mask of the low 62 bits.
+const M62: u64 = u64::MAX >> 2;
+
+/// The number of batches of 62 divsteps. For inputs of d = 574 bits, the bound of Bernstein and
+/// Yang, (49 * d + 57) / 17 = 1657.x, requires 1658 divsteps, and 27 * 62 = 1674.
+const BATCHES: usize = 27;
+
+/// An integer in base 2^62, of which all limbs except the top one are in [0, 2^62).
+type Signed62 = [i64; LIMBS];
+
+/// The transition matrix [[u, v], [q, r]] of 62 divsteps, scaled by 2^62.
+struct Transition {
+    u: i64,
+    v: i64,
+    q: i64,
+    r: i64,
+}
+
+/// An odd modulus of at most 574 bits, in the representation used for inversion.
+pub(crate) struct Modulus {
+    modulus: Signed62,
+    /// modulus^(-1) mod 2^62
+    modulus_inv62: u64,
+}
+
+impl Modulus {
+    pub(crate) const fn new(modulus: &BigInt<9>) -> Self {
+        // Newton's iteration doubles the number of correct low bits of the inverse each time.
+        let mut inv = 1u64;
+        let mut i = 0;
+        while i < 6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(modulus.0[0].wrapping_mul(inv)));
+            i += 1;
+        }
+
+        Self {
+            modulus: to_signed62(modulus),
+            modulus_inv62: inv & M62,
+        }
+    }
+}
+
+/// Returns x^(-1) * scale modulo the modulus, for x in [1, modulus) and scale in [0, modulus),
+/// in constant time.
+pub(crate) fn inverse(x: &BigInt<9>, scale: &BigInt<9>, modulus: &Modulus) -> BigInt<9> {
+    // The divsteps keep d * x = f * scale and e * x = g * scale modulo the modulus, starting
+    // from f = modulus and g = x. They end with g = 0 and f = +/-gcd(modulus, x) = +/-1.
+    let mut d = [0; LIMBS];
+    let mut e = to_signed62(scale);
+    let mut f = modulus.modulus;
+    let mut g = to_signed62(x);
+    let mut eta = -1;
+    for _ in 0..BATCHES {
+        let t = divsteps_62(&mut eta, f[0] as u64, g[0] as u64);
+        update_de(&mut d, &mut e, &t, modulus);
+        update_fg(&mut f, &mut g, &t);
+    }
+
+    normalize(&mut d, f[LIMBS - 1], modulus);
+    from_signed62(&d)
+}
+
+/// Performs 62 divsteps on the low limbs of f and g, where eta = -delta, and returns the
+/// transition matrix, without branches.
+fn divsteps_62(eta: &mut i64, f0: u64, g0: u64) -> Transition {
+    // The matrix entries are in [-2^62, 2^62], represented as integers modulo 2^64.
+    let (mut u, mut v, mut q, mut r) = (1u64, 0u64, 0u64, 1u64);
+    let (mut f, mut g) = (f0, g0);
+
+    for _ in 0..62 {
+        // Masks for eta < 0, that is, delta > 0, and for g being odd.
+        let c1 = (*eta >> 63) as u64;
+        let c2 = 0u64.wrapping_sub(g & 1);
+
+        // Conditionally negated versions of f, u, v, which are added to g, q, r if g is odd.
+        let x = (f ^ c1).wrapping_sub(c1);
+        let y = (u ^ c1).wrapping_sub(c1);
+        let z = (v ^ c1).wrapping_sub(c1);
+        g = g.wrapping_add(x & c2);
+        q = q.wrapping_add(y & c2);
+        r = r.wrapping_add(z & c2);
+
+        // If delta > 0 and g is odd, swap: f, u, v become the old g, q, r, and delta = 1 - delta.
+        // Otherwise, delta = 1 + delta.
+        let c1 = c1 & c2;
+        *eta = (*eta ^ c1 as i64) - (c1 as i64 + 1);
+        f = f.wrapping_add(g & c1);
+        u = u.wrapping_add(q & c1);
+        v = v.wrapping_add(r & c1);
+
+        g >>= 1;
+        u <<= 1;
+        v <<= 1;
+    }
+
+    Transition {
+        u: u as i64,
+        v: v as i64,
+        q: q as i64,
+        r: r as i64,
+    }
+}
+
+/// Sets [d, e] = t * [d, e] / 2^62 modulo the modulus, keeping d and e in
+/// (-2 * modulus, modulus).
+fn update_de(d: &mut Signed62, e: &mut Signed62, t: &Transition, modulus: &Modulus) {
+    let (u, v, q, r) = (t.u, t.v, t.q, t.r);
+    let m = &modulus.modulus;
+
+    // Add [u, q] * modulus if d is negative, and [v, r] * modulus if e is negative.
+    let sd = d[LIMBS - 1] >> 63;
+    let se = e[LIMBS - 1] >> 63;
+    let mut md = (u & sd) + (v & se);
+    let mut me = (q & sd) + (r & se);
+
+    let mut cd = u as i128 * d[0] as i128 + v as i128 * e[0] as i128;
+    let mut ce = q as i128 * d[0] as i128 + r as i128 * e[0] as i128;
+
+    // Correct md and me such that t * [d, e] + modulus * [md, me] has 62 zero low bits.
+    md -= (modulus
+        .modulus_inv62
+        .wrapping_mul(cd as u64)
+        .wrapping_add(md as u64)
+        & M62) as i64;
+    me -= (modulus
+        .modulus_inv62
+        .wrapping_mul(ce as u64)
+        .wrapping_add(me as u64)
+        & M62) as i64;
+    cd += m[0] as i128 * md as i128;
+    ce += m[0] as i128 * me as i128;
+    cd >>= 62;
+    ce >>= 62;
+
+    // Shift the remaining limbs down by one as they are computed.
+    for i in 1..LIMBS {
+        cd += u as i128 * d[i] as i128 + v as i128 * e[i] as i128 + m[i] as i128 * md as i128;
+        ce += q as i128 * d[i] as i128 + r as i128 * e[i] as i128 + m[i] as i128 * me as i128;
+        d[i - 1] = (cd as u64 & M62) as i64;
+        e[i - 1] = (ce as u64 & M62) as i64;
+        cd >>= 62;
+        ce >>= 62;
+    }
+    d[LIMBS - 1] = cd as i64;
+    e[LIMBS - 1] = ce as i64;
+}
+
+/// Sets [f, g] = t * [f, g] / 2^62, which is exact.
+fn update_fg(f: &mut Signed62, g: &mut Signed62, t: &Transition) {
+    let (u, v, q, r) = (t.u as i128, t.v as i128, t.q as i128, t.r as i128);
+
+    let mut cf = u * f[0] as i128 + v * g[0] as i128;
+    let mut cg = q * f[0] as i128 + r * g[0] as i128;
+    cf >>= 62;
+    cg >>= 62;
+
+    for i in 1..LIMBS {
+        cf += u * f[i] as i128 + v * g[i] as i128;
+        cg += q * f[i] as i128 + r * g[i] as i128;
+        f[i - 1] = (cf as u64 & M62) as i64;
+        g[i - 1] = (cg as u64 & M62) as i64;
+        cf >>= 62;
+        cg >>= 62;
+    }
+    f[LIMBS - 1] = cf as i64;
+    g[LIMBS - 1] = cg as i64;
+}
+
+/// Brings r from (-2 * modulus, modulus) to [0, modulus), after negating it if sign < 0.
+fn normalize(r: &mut Signed62, sign: i64, modulus: &Modulus) {
+    let m = &modulus.modulus;
+
+    // Add the modulus if r is negative, and then negate if requested, so that r is in
+    // (-modulus, modulus).
+    let cond_add = r[LIMBS - 1] >> 63;
+    let cond_negate = sign >> 63;
+    for i in 0..LIMBS {
+        r[i] += m[i] & cond_add;
+        r[i] = (r[i] ^ cond_negate) - cond_negate;
+    }
+    propagate_carries(r);
+
+    // Add the modulus again if r is still negative.
+    let cond_add = r[LIMBS - 1] >> 63;
+    for i in 0..LIMBS {
+        r[i] += m[i] & cond_add;
+    }
+    propagate_carries(r);
+}
+
+/// Brings all limbs of r except the top one to [0, 2^62).
+fn propagate_carries(r: &mut Signed62) {
+    for i in 0..LIMBS - 1 {
+        r[i + 1] += r[i] >> 62;
+        r[i] &= M62 as i64;
+    }
+}
+
+const fn to_signed62(a: &BigInt<9>) -> Signed62 {
+    let mut r = [0; LIMBS];
+    let mut i = 0;
+    while i < LIMBS {
+        let (limb, shift) = (62 * i / 64, 62 * i % 64);
+        let mut bits = if limb < 9 { a.0[limb] >> shift } else { 0 };
+        if shift > 2 && limb + 1 < 9 {
+            bits |= a.0[limb + 1] << (64 - shift);
+        }
+        r[i] = (bits & M62) as i64;
+        i += 1;
+    }
+    r
+}
+
+/// Converts an integer in [0, 2^576) back, which must have all limbs in [0, 2^62).
+fn from_signed62(a: &Signed62) -> BigInt<9> {
+    let mut r = [0u64; 9];
+    for (i, &bits) in a.iter().enumerate() {
+        let (limb, shift) = (62 * i / 64, 62 * i % 64);
+        if limb < 9 {
+            r[limb] |= (bits as u64) << shift;
+        }
+        if shift > 2 && limb + 1 < 9 {
+            r[limb + 1] |= (bits as u64) >> (64 - shift);
+        }
+    }
+    BigInt::new(r)
+}
@@ -0,0 +1,54 @@
+//! Point deserialization for the curves over the Yafa base fields and their extensions, which
+//! decompresses with the square roots specialized to each field instead of the generic
+//! Tonelli-Shanks of `ark_ff`.
+
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig, SWFlags};
+use ark_ff::Field;
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalDeserializeWithFlags, Compress, Read, SerializationError, Valid,
+    Validate,
+};
+
+/// Deserializes a point like the default [`SWCurveConfig::deserialize_with_mode`], but
+/// decompresses it with `sqrt` instead of the generic square root of the base field.
+pub(crate) fn deserialize_with_sqrt<P: SWCurveConfig, R: Read>(
+    mut reader: R,
+    compress: Compress,
+    validate: Validate,
+    sqrt: fn(&P::BaseField) -> Option<P::BaseField>,
+) -> Result<Affine<P>, SerializationError> {
+    let (x, y, flags) = match compress {
+        Compress::Yes => {
+            let (x, flags): (P::BaseField, SWFlags) =
+                CanonicalDeserializeWithFlags::deserialize_with_flags(&mut reader)?;
+            let y = match flags.is_positive() {
+                None => P::BaseField::ZERO,
+                Some(is_positive) => {
+                    let y = sqrt(&(P::COEFF_B + &((x.square() + &P::COEFF_A) * &x)))
+                        .ok_or(SerializationError::InvalidData)?;
+                    // As in SWFlags::from_y_coordinate, y is positive if y <= -y.
+                    match (y <= -y) == is_positive {
+                        true => y,
+                        false => -y,
+                    }
+                }
+            };
+            (x, y, flags)
+        }
+        Compress::No => {
+            let x = P::BaseField::deserialize_with_mode(&mut reader, compress, validate)?;
+            let (y, flags): (P::BaseField, SWFlags) =
+                CanonicalDeserializeWithFlags::deserialize_with_flags(&mut reader)?;
+            (x, y, flags)
+        }
+    };
+
+    if flags.is_infinity() {
+        return Ok(Affine::identity());
+    }
+    let point = Affine::new_unchecked(x, y);
+    if let Validate::Yes = validate {
+        point.check()?;
+    }
+    Ok(point)
+}
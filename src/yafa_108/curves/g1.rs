@@ -1,13 +1,22 @@
 use ark_ec::{
+    hashing::map_to_curve_hasher::MapToCurveBasedHasher,
     models::{short_weierstrass::SWCurveConfig, CurveConfig},
     short_weierstrass::{Affine, Projective},
     AffineRepr, CurveGroup,
 };
-use ark_ff::{Field, MontFp};
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_ff::{BigInt, Field, MontFp};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Validate,
+};
 use ark_std::vec::Vec;
+use sha2::{Sha256, Sha512};
 
-use crate::yafa_108::{Fq, Fr};
+use crate::serialization::deserialize_with_sqrt;
+use crate::{
+    glv::{self, EndomorphismConfig, GlvConfig},
+    hashing::{SvdwConfig, SvdwMap, XmdFieldHasher},
+    yafa_108::{Fq, FqConfig, Fr, FrConfig},
+};
 
 pub type G1Affine = Affine<Parameters>;
 pub type G1Projective = Projective<Parameters>;
@@ -71,8 +80,9 @@ impl CurveConfig for Parameters {
 
     /// COFACTOR^(-1) mod r =
     /// 29525709254955688747989059845541707767137581572857490857189440336502437830996
-    const COFACTOR_INV: Fr =
-        MontFp!("29525709254955688747989059845541707767137581572857490857189440336502437830996");
+    const COFACTOR_INV: Fr = FrConfig::const_from_bigint(BigInt!(
+        "29525709254955688747989059845541707767137581572857490857189440336502437830996"
+    ));
 }
 
 impl SWCurveConfig for Parameters {
@@ -84,8 +94,93 @@ impl SWCurveConfig for Parameters {
 
     /// AFFINE_GENERATOR_COEFFS = (G1_GENERATOR_X, G1_GENERATOR_Y)
     const GENERATOR: G1Affine = G1Affine::new_unchecked(G1_GENERATOR_X, G1_GENERATOR_Y);
+
+    /// Decompresses with [`FqConfig::sqrt`].
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<G1Affine, SerializationError> {
+        deserialize_with_sqrt(reader, compress, validate, FqConfig::sqrt)
+    }
+
+    /// Multiplies with the GLV method for scalars below r. The base must be in G1, as for
+    /// other points the result is not the multiple.
+    fn mul_projective(base: &G1Projective, scalar: &[u64]) -> G1Projective {
+        glv::mul_projective(base, scalar)
+    }
+
+    /// Multiplies with the GLV method for scalars below r, as [`Self::mul_projective`].
+    fn mul_affine(base: &G1Affine, scalar: &[u64]) -> G1Projective {
+        glv::mul_projective(&base.into_group(), scalar)
+    }
+
+    /// Splits the scalars with the GLV method.
+    fn msm(bases: &[G1Affine], scalars: &[Fr]) -> Result<G1Projective, usize> {
+        glv::msm(bases, scalars)
+    }
+
+    /// Multiplies by the effective cofactor, which halves the length of the cofactor.
+    fn clear_cofactor(p: &G1Affine) -> G1Affine {
+        let p = G1Projective::from(*p);
+        let phi = <Self as EndomorphismConfig<2>>::endomorphism(&p);
+        glv::linear_combination(&[p, phi], &EFFECTIVE_COFACTOR).into_affine()
+    }
+
+    /// Checks that a1 + b1 * phi maps the point to zero for the first GLV basis vector (a1, b1),
+    /// of which the kernel is G1 as it has degree a1^2 - a1 * b1 + b1^2 = r.
+    fn is_in_correct_subgroup_assuming_on_curve(p: &G1Affine) -> bool {
+        glv::is_in_correct_subgroup(p)
+    }
+}
+
+impl GlvConfig for Parameters {
+    /// ENDO_COEFF =
+    /// 26889017910506495156320058129568772566653933834480888250851357865717474104627594400663718411564763129536634204743601058030506421687843844062271438195269332524424351101183828
+    const ENDO_COEFF: Fq = MontFp!("26889017910506495156320058129568772566653933834480888250851357865717474104627594400663718411564763129536634204743601058030506421687843844062271438195269332524424351101183828");
 }
 
+impl SvdwConfig for Parameters {
+    /// Z = 1
+    const Z: Fq = MontFp!("1");
+
+    fn sqrt(fe: &Fq) -> Option<Fq> {
+        FqConfig::sqrt(fe)
+    }
+}
+
+/// The suite YAFA108G1_XMD:SHA-256_SVDW_RO_ of RFC 9380, hashing to G1 with
+/// expand_message_xmd with SHA-256, the SvdW map and the effective cofactor.
+pub type G1Sha256Hasher =
+    MapToCurveBasedHasher<G1Projective, XmdFieldHasher<Sha256>, SvdwMap<Parameters>>;
+
+/// The suite YAFA108G1_XMD:SHA-512_SVDW_RO_ of RFC 9380, which is [`G1Sha256Hasher`] with
+/// SHA-512.
+pub type G1Sha512Hasher =
+    MapToCurveBasedHasher<G1Projective, XmdFieldHasher<Sha512>, SvdwMap<Parameters>>;
+
+/// The suite ID of [`G1Sha256Hasher`], to be part of the DSTs of applications.
+pub const G1_SHA256_SUITE_ID: &[u8] = b"YAFA108G1_XMD:SHA-256_SVDW_RO_";
+
+/// The suite ID of [`G1Sha512Hasher`].
+pub const G1_SHA512_SUITE_ID: &[u8] = b"YAFA108G1_XMD:SHA-512_SVDW_RO_";
+
+/// The effective cofactor a + b * phi =
+/// -350136509637364995336420522718240729632011025205 +
+/// 538233698310663448678130747574132993962198489281 * phi,
+/// a generator of the ideal of End(E) = Z[phi] of norm COFACTOR that divides pi - 1 for the
+/// Frobenius endomorphism pi, so that it kills the points of E(Fq) of order dividing COFACTOR.
+const EFFECTIVE_COFACTOR: [(bool, BigInt<4>); 2] = [
+    (
+        true,
+        BigInt!("350136509637364995336420522718240729632011025205"),
+    ),
+    (
+        false,
+        BigInt!("538233698310663448678130747574132993962198489281"),
+    ),
+];
+
 /// G1_GENERATOR_X =
 /// 34224882689487856897895479418745239985629366350609644482426457479519960220365127786522998842348723780309585364044838207298438594720820768669193775884940942820440641403063878
 ///
@@ -1,23 +1,66 @@
 use ark_ec::{
+    hashing::map_to_curve_hasher::MapToCurveBasedHasher,
     models::CurveConfig,
     short_weierstrass::{Affine, Projective, SWCurveConfig},
     AffineRepr, CurveGroup,
 };
-use ark_ff::{Field, MontFp};
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use ark_std::vec::Vec;
+use ark_ff::{fields::BitIteratorBE, BigInt, Field, MontFp};
+use ark_serialize::*;
+use ark_std::{vec, vec::Vec, One};
+use sha2::{Sha256, Sha512};
 
-use crate::yafa_108::{Fq, Fq3, Fr};
+use crate::serialization::deserialize_with_sqrt;
+use crate::{
+    glv::{self, EndomorphismConfig, GlvConfig},
+    hashing::{SvdwConfig, SvdwMap, XmdFieldHasher},
+    yafa_108::{Fq, FqConfig, Fr, FrConfig, ATE_LOOP_COUNT_1, ATE_LOOP_COUNT_2},
+};
 
 pub type G2Affine = Affine<Parameters>;
 pub type G2Projective = Projective<Parameters>;
 
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Parameters;
+
 #[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
-pub struct G2Prepared(pub G2Affine);
+pub struct G2Prepared {
+    // Stores the coefficients of the line evaluations as calculated in
+    // https://eprint.iacr.org/2013/722.pdf, for the Miller loop of
+    // ATE_LOOP_COUNT_1 at Q and of ATE_LOOP_COUNT_2 at psi(Q)
+    pub ell_coeffs_1: Vec<EllCoeff<Fq>>,
+    pub ell_coeffs_2: Vec<EllCoeff<Fq>>,
+    pub infinity: bool,
+}
+
+pub(crate) type EllCoeff<F> = (F, F, F);
+
+#[derive(Clone, Copy, Debug)]
+struct G2HomProjective {
+    x: Fq,
+    y: Fq,
+    z: Fq,
+}
+
+impl Default for G2Prepared {
+    fn default() -> Self {
+        Self::from(G2Affine::generator())
+    }
+}
 
 impl From<G2Affine> for G2Prepared {
-    fn from(other: G2Affine) -> Self {
-        G2Prepared(other)
+    fn from(q: G2Affine) -> Self {
+        match q.is_identity() {
+            true => G2Prepared {
+                ell_coeffs_1: vec![],
+                ell_coeffs_2: vec![],
+                infinity: true,
+            },
+            false => G2Prepared {
+                ell_coeffs_1: ell_coeffs(&q, ATE_LOOP_COUNT_1),
+                ell_coeffs_2: ell_coeffs(&psi(&q), ATE_LOOP_COUNT_2),
+                infinity: false,
+            },
+        }
     }
 }
 
@@ -29,7 +72,7 @@ impl From<G2Projective> for G2Prepared {
 
 impl<'a> From<&'a G2Affine> for G2Prepared {
     fn from(other: &'a G2Affine) -> Self {
-        G2Prepared(*other)
+        G2Prepared::from(*other)
     }
 }
 
@@ -41,114 +84,219 @@ impl<'a> From<&'a G2Projective> for G2Prepared {
 
 impl G2Prepared {
     pub fn is_zero(&self) -> bool {
-        self.0.is_identity()
+        self.infinity
     }
 }
 
-impl Default for G2Prepared {
-    fn default() -> Self {
-        G2Prepared(G2Affine::generator())
+/// Returns the line coefficients of the Miller loop of `loop_count` at the non-zero point `q`,
+/// in the order in which the Miller loop consumes them.
+pub(crate) fn ell_coeffs(q: &G2Affine, loop_count: impl AsRef<[u64]>) -> Vec<EllCoeff<Fq>> {
+    let two_inv = Fq::one().double().inverse().unwrap();
+    let mut ell_coeffs = vec![];
+    let mut r = G2HomProjective {
+        x: q.x,
+        y: q.y,
+        z: Fq::one(),
+    };
+
+    for bit in BitIteratorBE::without_leading_zeros(loop_count).skip(1) {
+        ell_coeffs.push(doubling_step(&mut r, &two_inv));
+
+        if bit {
+            ell_coeffs.push(addition_step(&mut r, q));
+        }
     }
+    ell_coeffs
 }
 
-#[derive(Clone, Default, PartialEq, Eq)]
-pub struct Parameters;
+/// The untwist-Frobenius-twist endomorphism psi(x, y) = (x * PSI_COEFF_X, y * PSI_COEFF_Y),
+/// which acts on G2 as multiplication by q.
+pub fn psi(p: &G2Affine) -> G2Affine {
+    G2Affine::new_unchecked(p.x * &PSI_COEFF_X, p.y * &PSI_COEFF_Y)
+}
+
+fn doubling_step(r: &mut G2HomProjective, two_inv: &Fq) -> EllCoeff<Fq> {
+    // Formula for line function when working with
+    // homogeneous projective coordinates.
+
+    let a = r.x * &r.y * two_inv;
+    let b = r.y.square();
+    let c = r.z.square();
+    let e = Parameters::COEFF_B * &(c.double() + &c);
+    let f = e.double() + &e;
+    let g = (b + &f) * two_inv;
+    let h = (r.y + &r.z).square() - &(b + &c);
+    let i = e - &b;
+    let j = r.x.square();
+    let e_square = e.square();
+
+    r.x = a * &(b - &f);
+    r.y = g.square() - &(e_square.double() + &e_square);
+    r.z = b * &h;
+
+    // This is a multiplicative twist
+    (-h, j.double() + &j, i)
+}
+
+fn addition_step(r: &mut G2HomProjective, q: &G2Affine) -> EllCoeff<Fq> {
+    // Formula for line function when working with
+    // homogeneous projective coordinates.
+
+    let theta = r.y - &(q.y * &r.z);
+    let lambda = r.x - &(q.x * &r.z);
+    let c = theta.square();
+    let d = lambda.square();
+    let e = lambda * &d;
+    let f = r.z * &c;
+    let g = r.x * &d;
+    let h = e + &f - &g.double();
+    r.x = lambda * &h;
+    r.y = theta * &(g - &h) - &(e * &r.y);
+    r.z *= &e;
+    let j = theta * &q.x - &(lambda * &q.y);
+
+    // This is a multiplicative twist
+    (lambda, -theta, j)
+}
 
 impl CurveConfig for Parameters {
-    type BaseField = Fq3;
+    type BaseField = Fq;
     type ScalarField = Fr;
 
     /// COFACTOR =
-    /// 726726886795224844605862069009898940861687901303075691762904985779862199643629549368160648
-    /// 101516193501672955437075875652993924163644219867857332814382981687662806476128613460127897
-    /// 964217230378169042051917041793548330220695206406784608340953286143661873591990564852877575
-    /// 735414956858902999883887464694646957850111726423572809242652238015382526099588420834286969
-    /// 916870199868835572386002046396865439392409275568594557647057684332132868235236100
+    /// 600746357873917083993205832519317177466334726496174638989123538389804293458724181786504051742052
     #[rustfmt::skip]
     const COFACTOR: &'static [u64] = &[
-        0x55b65ba67d349304,
-        0x19bfd908aa18a1d0,
-        0xea1b02ffa4730d50,
-        0x5f30f2832574c04e,
-        0xe95715e341a98565,
-        0x550746195d560fd8,
-        0xbadab12caadb75a1,
-        0x94da6c018200630a,
-        0xa9b498e4a406cc19,
-        0xe63352507f8897e9,
-        0x7fa6743474161d96,
-        0x911d512e19981a36,
-        0x125b50ed21e62534,
-        0x2ee37326b89dafc0,
-        0x595fca9878419c72,
-        0x0f27e144d8e957dc,
-        0xa0461c02e6c10c7c,
-        0x11b8a324657e0e5c,
-        0xa1103ff7e3dbcdb6,
-        0xc259971d1b71d23e,
-        0xe2afc34dc49f0dbe,
-        0xd71c79058c75e34a,
-        0x16c80007accd8dc
+        0xed29225b9506d564,
+        0x4360c489b2a69d35,
+        0xd497d8292a77e57a,
+        0x2e5856438790676e,
+        0x4800000815ea74e0
     ];
 
     /// COFACTOR^(-1) mod r =
-    /// 22582721958699120459790815271456780810858829859261718489478745480578972630174
-    const COFACTOR_INV: Fr =
-        MontFp!("22582721958699120459790815271456780810858829859261718489478745480578972630174");
+    /// 42311302089931857498978288172298425440193521822113321900972413855865094780028
+    const COFACTOR_INV: Fr = FrConfig::const_from_bigint(BigInt!(
+        "42311302089931857498978288172298425440193521822113321900972413855865094780028"
+    ));
 }
 
 impl SWCurveConfig for Parameters {
-    /// TWIST = (0, 1, 0)
-    /// COEFF_A = G1::COEFF_A * TWIST^2
-    const COEFF_A: Fq3 = Fq3::new(Fq::ZERO, Fq::ZERO, Fq::ZERO);
+    /// COEFF_A = 0
+    const COEFF_A: Fq = Fq::ZERO;
 
-    /// TWIST = (0, 1, 0)
-    /// COEFF_B = G1::COEFF_B * TWIST^3 = G1::COEFF_B * NONRESIDE
-    const COEFF_B: Fq3 = Fq3::new(MontFp!("62099136"), Fq::ZERO, Fq::ZERO);
+    /// COEFF_B = G1::COEFF_B * Fq3::NONRESIDUE = 62099136
+    ///
+    /// This is the sextic twist of G1 that is mapped into G1 over Fq6 by
+    /// (x, y) -> (x / Y^2, y / Y^3), where Y^2 = Fq6::NONRESIDUE and Y^6 = Fq3::NONRESIDUE.
+    const COEFF_B: Fq = MontFp!("62099136");
 
     /// AFFINE_GENERATOR_COEFFS = (G2_GENERATOR_X, G2_GENERATOR_Y)
     const GENERATOR: G2Affine = G2Affine::new_unchecked(G2_GENERATOR_X, G2_GENERATOR_Y);
-    // This is the point (u + 3, 16399745525914760875122822753296350670146148646493981347801019658
-    // 4974651832260489547154965730230287901162813838819504218501063654238729264453808620564961547
-    // 24756285267266888*u^2 + 1428725444905761342823912326923276592561970382446970420290187715131
-    // 0093048222249908017251200009970845728118751765949951105910650164572175833612033146936098637
-    // 881156817742266*u + 67581327726597132966749957858127838644323731843665910733072840363027716
-    // 0345581250943954549492116413341182297640653467835630039837549443336827320517413471064766205
-    // 6414848650)
-    // multiplied by the cofactor
-    //
     // The number of points on the curve
-    // 4207461226347482993393603908999921895333327526414550509650736118016794992267577681084392484
-    // 9809657360885733735461803068663448419618998350346139732225192923396886978143021447634012407
-    // 9773466211959941796770179681655789917802809899895459449762549378355371967193112709155484805
-    // 2083608412799744718833303344955634533389915438277771384346636366046804113571184420208061177
-    // 8517550049888741382347585816538884386662790550643273998680667111320791291600388960443712456
-    // 250454022861569583984825897441500255762247915491861374004958900
+    // 3478083793996464891678339190909783846671415720954951395674166166842008052739670559059743562
+    // 7928888851567361919012832730719832722316187160078328943413553309822950951171795348
+
+    /// Decompresses with [`FqConfig::sqrt`].
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<G2Affine, SerializationError> {
+        deserialize_with_sqrt(reader, compress, validate, FqConfig::sqrt)
+    }
+
+    /// Multiplies with the GLS method for scalars below r. The base must be in G2, as
+    /// for other points the result is not the multiple.
+    fn mul_projective(base: &G2Projective, scalar: &[u64]) -> G2Projective {
+        glv::mul_projective(base, scalar)
+    }
+
+    /// Multiplies with the GLS method for scalars below r, as [`Self::mul_projective`].
+    fn mul_affine(base: &G2Affine, scalar: &[u64]) -> G2Projective {
+        glv::mul_projective(&base.into_group(), scalar)
+    }
+
+    /// Splits the scalars with the GLS method.
+    fn msm(bases: &[G2Affine], scalars: &[Fr]) -> Result<G2Projective, usize> {
+        glv::msm(bases, scalars)
+    }
+
+    /// Multiplies by the effective cofactor, which halves the length of the cofactor.
+    fn clear_cofactor(p: &G2Affine) -> G2Affine {
+        let p = G2Projective::from(*p);
+        let psi = -<Self as EndomorphismConfig<2>>::endomorphism(&p);
+        glv::linear_combination(&[p, psi], &EFFECTIVE_COFACTOR).into_affine()
+    }
+
+    /// Checks that a1 + b1 * phi = a1 - b1 * psi maps the point to zero for the first GLV basis
+    /// vector (a1, b1), of which the kernel is G2 as it has degree a1^2 - a1 * b1 + b1^2 = r.
+    fn is_in_correct_subgroup_assuming_on_curve(p: &G2Affine) -> bool {
+        glv::is_in_correct_subgroup(p)
+    }
 }
 
-const G2_GENERATOR_X: Fq3 = Fq3::new(G2_GENERATOR_X_C0, G2_GENERATOR_X_C1, G2_GENERATOR_X_C2);
-const G2_GENERATOR_Y: Fq3 = Fq3::new(G2_GENERATOR_Y_C0, G2_GENERATOR_Y_C1, G2_GENERATOR_Y_C2);
+/// As q = -LAMBDA mod r, psi = -phi for the GLV endomorphism phi(x, y) = (x * PSI_COEFF_X, y), so
+/// the GLS decomposition with psi is the GLV decomposition with phi.
+impl GlvConfig for Parameters {
+    /// ENDO_COEFF = PSI_COEFF_X
+    const ENDO_COEFF: Fq = PSI_COEFF_X;
+}
 
-/// G2_GENERATOR_X_C0 =
-/// 25392698904884709132999037760254876657725187667682696813186827970865787471891640764049212417291921682011505465948857004062296822796470822300734123777520888334658977998568595
-pub const G2_GENERATOR_X_C0: Fq = MontFp!("25392698904884709132999037760254876657725187667682696813186827970865787471891640764049212417291921682011505465948857004062296822796470822300734123777520888334658977998568595");
+impl SvdwConfig for Parameters {
+    /// Z = 1
+    const Z: Fq = MontFp!("1");
 
-/// G2_GENERATOR_X_C1 =
-/// 0
-pub const G2_GENERATOR_X_C1: Fq = Fq::ZERO;
+    fn sqrt(fe: &Fq) -> Option<Fq> {
+        FqConfig::sqrt(fe)
+    }
+}
 
-/// G2_GENERATOR_X_C2 =
-/// 0
-pub const G2_GENERATOR_X_C2: Fq = Fq::ZERO;
+/// The suite YAFA108G2_XMD:SHA-256_SVDW_RO_ of RFC 9380, hashing to G2 with
+/// expand_message_xmd with SHA-256, the SvdW map on the twist and the effective cofactor.
+///
+/// The sextic twist is defined over Fq, so this hashes to Fq as for G1 rather than to Fq3.
+pub type G2Sha256Hasher =
+    MapToCurveBasedHasher<G2Projective, XmdFieldHasher<Sha256>, SvdwMap<Parameters>>;
 
-/// G2_GENERATOR_Y_C0 =
-/// 4396708447983789823202089491402954890042030420320791355295989970672982059020950821852365967834639548445057566914035829163770719788716368901156926304377953920559312066532663
-pub const G2_GENERATOR_Y_C0: Fq = MontFp!("4396708447983789823202089491402954890042030420320791355295989970672982059020950821852365967834639548445057566914035829163770719788716368901156926304377953920559312066532663");
+/// The suite YAFA108G2_XMD:SHA-512_SVDW_RO_ of RFC 9380, which is [`G2Sha256Hasher`] with
+/// SHA-512.
+pub type G2Sha512Hasher =
+    MapToCurveBasedHasher<G2Projective, XmdFieldHasher<Sha512>, SvdwMap<Parameters>>;
+
+/// The suite ID of [`G2Sha256Hasher`], to be part of the DSTs of applications.
+pub const G2_SHA256_SUITE_ID: &[u8] = b"YAFA108G2_XMD:SHA-256_SVDW_RO_";
 
-/// G2_GENERATOR_Y_C1 =
-/// 0
-pub const G2_GENERATOR_Y_C1: Fq = Fq::ZERO;
+/// The suite ID of [`G2Sha512Hasher`].
+pub const G2_SHA512_SUITE_ID: &[u8] = b"YAFA108G2_XMD:SHA-512_SVDW_RO_";
 
-/// G2_GENERATOR_Y_C2 =
-/// 0
-pub const G2_GENERATOR_Y_C2: Fq = Fq::ZERO;
+/// The effective cofactor a + b * psi =
+/// -350136503206563797517678249969639865500302071982 +
+/// -538233703893565900712344667130941122326715764706 * psi,
+/// a generator of the ideal of End(E') = Z[psi] of norm COFACTOR that divides pi - 1 for the
+/// Frobenius endomorphism pi, so that it kills the points of E'(Fq) of order dividing COFACTOR.
+const EFFECTIVE_COFACTOR: [(bool, BigInt<4>); 2] = [
+    (
+        true,
+        BigInt!("350136503206563797517678249969639865500302071982"),
+    ),
+    (
+        true,
+        BigInt!("538233703893565900712344667130941122326715764706"),
+    ),
+];
+
+/// PSI_COEFF_X = Y^(2 * (1 - q)) = Fq3::NONRESIDUE^((1 - q) / 3) =
+/// 7891820029458153760463333779529065900060223375068625705890303802702606422769111189933903712579664581217514620699178559520642750983460643979247817048104789503567679423896212
+pub const PSI_COEFF_X: Fq = MontFp!("7891820029458153760463333779529065900060223375068625705890303802702606422769111189933903712579664581217514620699178559520642750983460643979247817048104789503567679423896212");
+
+/// PSI_COEFF_Y = Y^(3 * (1 - q)) = Fq3::NONRESIDUE^((1 - q) / 2) = -1
+pub const PSI_COEFF_Y: Fq = MontFp!("-1");
+
+/// G2_GENERATOR_X =
+/// 25392698904884709132999037760254876657725187667682696813186827970865787471891640764049212417291921682011505465948857004062296822796470822300734123777520888334658977998568595
+pub const G2_GENERATOR_X: Fq = MontFp!("25392698904884709132999037760254876657725187667682696813186827970865787471891640764049212417291921682011505465948857004062296822796470822300734123777520888334658977998568595");
+
+/// G2_GENERATOR_Y =
+/// 4396708447983789823202089491402954890042030420320791355295989970672982059020950821852365967834639548445057566914035829163770719788716368901156926304377953920559312066532663
+pub const G2_GENERATOR_Y: Fq = MontFp!("4396708447983789823202089491402954890042030420320791355295989970672982059020950821852365967834639548445057566914035829163770719788716368901156926304377953920559312066532663");
@@ -1,19 +1,35 @@
 use ark_ec::pairing::{MillerLoopOutput, PairingOutput};
-use ark_ec::{models::short_weierstrass::SWCurveConfig, pairing::Pairing};
+use ark_ec::{pairing::Pairing, AffineRepr};
 use ark_ff::{
     biginteger::BigInt,
     fields::{BitIteratorBE, Field},
-    CyclotomicMultSubgroup, One,
+    One,
 };
+use ark_std::{cfg_chunks_mut, vec::Vec};
 use itertools::Itertools;
 
-use crate::yafa_108::{Fq, Fq3, Fq6, Fr};
+use crate::{
+    cyclotomic::cyclotomic_multi_exp,
+    yafa_108::{Fq, Fq6, Fq6Config, Fr},
+};
 
 pub mod g1;
-pub use self::g1::{G1Affine, G1Prepared, G1Projective};
+pub use self::g1::{
+    G1Affine, G1Prepared, G1Projective, G1Sha256Hasher, G1Sha512Hasher, G1_SHA256_SUITE_ID,
+    G1_SHA512_SUITE_ID,
+};
 
 pub mod g2;
-pub use self::g2::{G2Affine, G2Prepared, G2Projective};
+pub use self::g2::{
+    G2Affine, G2Prepared, G2Projective, G2Sha256Hasher, G2Sha512Hasher, G2_SHA256_SUITE_ID,
+    G2_SHA512_SUITE_ID,
+};
+
+pub mod torus;
+pub use self::torus::TorusPairingOutput;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 #[cfg(test)]
 mod tests;
@@ -37,91 +53,98 @@ impl Pairing for Yafa {
         a: impl IntoIterator<Item = impl Into<Self::G1Prepared>>,
         b: impl IntoIterator<Item = impl Into<Self::G2Prepared>>,
     ) -> MillerLoopOutput<Self> {
-        let mut result = Self::TargetField::one();
-        a.into_iter().zip_eq(b).for_each(|(p, q)| {
-            let (p, q) = (p.into(), q.into());
-            result *= &Yafa::ate_miller_loop(&p.0, &q.0);
-        });
-
-        MillerLoopOutput(result)
+        let mut pairs = a
+            .into_iter()
+            .zip_eq(b)
+            .filter_map(|(p, q)| {
+                let (p, q): (G1Prepared, G2Prepared) = (p.into(), q.into());
+                match !p.is_zero() && !q.is_zero() {
+                    true => Some((p, q.ell_coeffs_1.into_iter(), q.ell_coeffs_2.into_iter())),
+                    false => None,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let f = cfg_chunks_mut!(pairs, 4)
+            .map(|pairs| {
+                let mut f = Self::TargetField::one();
+
+                // ATE_LOOP_COUNT_1 and ATE_LOOP_COUNT_2 have the same bit length, so
+                // the Miller loops at Q and at psi(Q) share all of their squarings.
+                let bits_1 = BitIteratorBE::without_leading_zeros(ATE_LOOP_COUNT_1).skip(1);
+                let bits_2 = BitIteratorBE::without_leading_zeros(ATE_LOOP_COUNT_2).skip(1);
+                for (bit_1, bit_2) in bits_1.zip_eq(bits_2) {
+                    Fq6Config::lazy_square_in_place(&mut f);
+                    for (p, coeffs_1, coeffs_2) in pairs.iter_mut() {
+                        Self::ell(&mut f, &coeffs_1.next().unwrap(), p);
+                        Self::ell(&mut f, &coeffs_2.next().unwrap(), p);
+                    }
+
+                    if bit_1 {
+                        for (p, coeffs_1, _) in pairs.iter_mut() {
+                            Self::ell(&mut f, &coeffs_1.next().unwrap(), p);
+                        }
+                    }
+                    if bit_2 {
+                        for (p, _, coeffs_2) in pairs.iter_mut() {
+                            Self::ell(&mut f, &coeffs_2.next().unwrap(), p);
+                        }
+                    }
+                }
+                f
+            })
+            .product::<Self::TargetField>();
+
+        MillerLoopOutput(f)
     }
 
     fn final_exponentiation(r: MillerLoopOutput<Self>) -> Option<PairingOutput<Self>> {
-        Some(PairingOutput(Yafa::final_exponentiation(&r.0)))
+        Yafa::final_exponentiation(&r.0).map(PairingOutput)
     }
 }
 
 impl Yafa {
+    /// The ate pairing with the loop count `t - 1`. This is kept as a reference, since the
+    /// optimal ate pairing computed by [`Pairing`] equals it raised to a fixed power.
     pub fn ate_pairing(p: &G1Affine, q: &G2Affine) -> GT {
-        Yafa::final_exponentiation(&Yafa::ate_miller_loop(p, q))
+        // The Miller loop output is invertible for any pair of group elements.
+        Yafa::final_exponentiation(&Yafa::ate_miller_loop(&p.into(), q)).unwrap()
     }
 
-    fn ate_miller_loop(p: &G1Affine, q: &G2Affine) -> GT {
-        let px = p.x;
-        let py = p.y;
-        let qx = q.x;
-        let qy = q.y;
-        let mut py_twist_squared = TWIST.square();
-        py_twist_squared.mul_assign_by_fp(&py);
-
-        let mut old_rx;
-        let mut old_ry;
-        let mut rx = qx;
-        let mut ry = qy;
+    fn ate_miller_loop(p: &G1Prepared, q: &G2Affine) -> GT {
         let mut f = Fq6::one();
+        if p.is_zero() || q.is_identity() {
+            return f;
+        }
+        let mut coeffs = g2::ell_coeffs(q, ATE_LOOP_COUNT).into_iter();
 
         // The for loop is executed for all bits (EXCEPT the MSB itself) of
-        // cp6_782_param_p (skipping leading zeros) in MSB to LSB order
+        // ATE_LOOP_COUNT (skipping leading zeros) in MSB to LSB order
         for bit in BitIteratorBE::without_leading_zeros(ATE_LOOP_COUNT).skip(1) {
-            old_rx = rx;
-            old_ry = ry;
-
-            let old_rx_square = old_rx.square();
-            let old_rx_square_3 = old_rx_square.double() + &old_rx_square;
-            let old_rx_square_3_a = old_rx_square_3 + &g2::Parameters::COEFF_A;
-            let old_ry_double_inverse = old_ry.double().inverse().unwrap();
-
-            let gamma = old_rx_square_3_a * &old_ry_double_inverse;
-            let gamma_twist = gamma * &TWIST;
-            let gamma_old_rx = gamma * &old_rx;
-            let mut gamma_twist_px = gamma_twist;
-            gamma_twist_px.mul_assign_by_fp(&px);
-
-            let x = py_twist_squared;
-            let y = gamma_old_rx - &old_ry - &gamma_twist_px;
-            let ell_rr_at_p: Fq6 = Fq6::new(x, y);
-
-            rx = gamma.square() - &old_rx.double();
-            ry = gamma * &(old_rx - &rx) - &old_ry;
-            f = f.square() * &ell_rr_at_p;
+            Fq6Config::lazy_square_in_place(&mut f);
+            Yafa::ell(&mut f, &coeffs.next().unwrap(), p);
 
             if bit {
-                old_rx = rx;
-                old_ry = ry;
-
-                let gamma = (old_ry - &qy) * &((old_rx - &qx).inverse().unwrap());
-                let gamma_twist = gamma * &TWIST;
-                let gamma_qx = gamma * &qx;
-                let mut gamma_twist_px = gamma_twist;
-                gamma_twist_px.mul_assign_by_fp(&px);
-
-                let x = py_twist_squared;
-                let y = gamma_qx - &qy - &gamma_twist_px;
-                let ell_rq_at_p: Fq6 = Fq6::new(x, y);
-
-                rx = gamma.square() - &old_rx - &qx;
-                ry = gamma * &(old_rx - &rx) - &old_ry;
-                f = f * &ell_rq_at_p;
+                Yafa::ell(&mut f, &coeffs.next().unwrap(), p);
             }
         }
         f
     }
 
-    fn final_exponentiation(value: &Fq6) -> GT {
-        let value_inv = value.inverse().unwrap();
-        let value_to_first_chunk = Yafa::final_exponentiation_first(value, &value_inv);
-        let value_inv_to_first_chunk = Yafa::final_exponentiation_first(&value_inv, value);
-        Yafa::final_exponentiation_last(&value_to_first_chunk, &value_inv_to_first_chunk)
+    // Evaluate the line function at point p. Untwisting the line
+    // c0 * y + c1 * x + c2 with (x, y) -> (x / Y^2, y / Y^3) and scaling by Y^3
+    // gives c2 + c1 * px * Y^2 + c0 * py * Y^3, where Y^2 = u.
+    fn ell(f: &mut Fq6, coeffs: &g2::EllCoeff<Fq>, p: &G1Prepared) {
+        let (px, py) = p.0.xy().unwrap();
+        Fq6Config::mul_by_014(f, &coeffs.2, &(coeffs.1 * px), &(coeffs.0 * py));
+    }
+
+    fn final_exponentiation(value: &Fq6) -> Option<GT> {
+        value.inverse().map(|value_inv| {
+            let value_to_first_chunk = Yafa::final_exponentiation_first(value, &value_inv);
+            let value_inv_to_first_chunk = Yafa::final_exponentiation_first(&value_inv, value);
+            Yafa::final_exponentiation_last(&value_to_first_chunk, &value_inv_to_first_chunk)
+        })
     }
 
     fn final_exponentiation_first(elt: &Fq6, elt_inv: &Fq6) -> Fq6 {
@@ -143,20 +166,24 @@ impl Yafa {
         let mut elt_q = elt.clone();
         elt_q.frobenius_map(1);
 
-        let w1_part = elt_q.cyclotomic_exp(&FINAL_EXPONENT_LAST_CHUNK_W1);
-        let w0_part = if FINAL_EXPONENT_LAST_CHUNK_W0_IS_NEG {
-            elt_inv.cyclotomic_exp(&FINAL_EXPONENT_LAST_CHUNK_ABS_OF_W0)
+        let w0_base = if FINAL_EXPONENT_LAST_CHUNK_W0_IS_NEG {
+            elt_inv
         } else {
-            elt.cyclotomic_exp(&FINAL_EXPONENT_LAST_CHUNK_ABS_OF_W0)
+            elt
         };
 
-        w1_part * &w0_part
+        cyclotomic_multi_exp(
+            &[*w0_base, elt_q],
+            &[
+                FINAL_EXPONENT_LAST_CHUNK_ABS_OF_W0,
+                FINAL_EXPONENT_LAST_CHUNK_W1,
+            ],
+            Fq6Config::cyclotomic_square_in_place,
+            Fq6Config::lazy_mul_assign,
+        )
     }
 }
 
-/// TWIST = (0, 1, 0)
-pub const TWIST: Fq3 = Fq3::new(Fq::ZERO, Fq::ONE, Fq::ZERO);
-
 /// ATE_IS_LOOP_COUNT_NEG = false
 pub const ATE_IS_LOOP_COUNT_NEG: bool = false;
 
@@ -170,33 +197,57 @@ pub const ATE_LOOP_COUNT: [u64; 5] = [
     0xc000000a,
 ];
 
-/// FINAL_EXPONENT_LAST_CHUNK_W0_IS_NEG = true
+/// The optimal ate pairing is computed from the short vector (ATE_LOOP_COUNT_1, ATE_LOOP_COUNT_2)
+/// of the lattice {(c0, c1) : c0 + c1 * q = 0 mod r}, as
+/// f_{ATE_LOOP_COUNT_1, Q}(P) * f_{ATE_LOOP_COUNT_2, Q}(P)^q
+///     = f_{ATE_LOOP_COUNT_1, Q}(P) * f_{ATE_LOOP_COUNT_2, psi(Q)}(P).
+/// The line through [ATE_LOOP_COUNT_1]Q and [ATE_LOOP_COUNT_2 * q]Q is vertical, since these
+/// points are opposite, so it is eliminated by the final exponentiation.
+/// See https://eprint.iacr.org/2008/096.pdf.
+///
+/// ATE_LOOP_COUNT_1 =
+/// 167089731525863132650062706797393049103
+pub const ATE_LOOP_COUNT_1: [u64; 2] = [0x5798dd761505760f, 0x7db44fcdd58467d6];
+
+/// ATE_LOOP_COUNT_2 =
+/// 108696677178804449820597827182464518980
+pub const ATE_LOOP_COUNT_2: [u64; 2] = [0x8c13773986cecb44, 0x51c637f275ea2b3b];
+
+/// The hard part of the final exponentiation is evaluated as
+/// w0 + w1 * q = FINAL_EXPONENT_MULTIPLIER * (q^2 - q + 1) / r,
+/// where (w0, w1) is a short vector of the lattice
+/// {(w0, w1) : w0 + w1 * q = 0 mod (q^2 - q + 1) / r},
+/// of about 446 bits instead of the 573 and 319 bits of the plain base-q digits.
+/// The pairing is thus the FINAL_EXPONENT_MULTIPLIER-th power of the one with the exponent
+/// (q^6 - 1) / r.
+///
+/// FINAL_EXPONENT_MULTIPLIER =
+/// 167089731525863132650062706797393049103
+pub const FINAL_EXPONENT_MULTIPLIER: [u64; 2] = [0x5798dd761505760f, 0x7db44fcdd58467d6];
+
+/// FINAL_EXPONENT_LAST_CHUNK_W0_IS_NEG = false
 pub const FINAL_EXPONENT_LAST_CHUNK_W0_IS_NEG: bool = false;
 
 /// FINAL_EXPONENT_LAST_CHUNK_ABS_OF_W0 =
-/// 19533729308556160109794825562832707092134991522349430861538344636908041560785866966036323918363729219562215110665533950746637708295543215802259030565596621823690292271103113
-pub const FINAL_EXPONENT_LAST_CHUNK_ABS_OF_W0: BigInt<9> = BigInt::new([
-    0x7d919d468ad5bc89,
-    0x851c91204bcf98dc,
-    0x1ccd844b0de55ff8,
-    0x36bd1be87c106c82,
-    0xcfe6ae45c3d21c71,
-    0x96d3c4ddb45de345,
-    0xe20009f1de3c5d1f,
-    0xc534aab128f9dd7a,
-    0x1437eba2e55524f8,
+/// 65299132928163694004965076206075798448265059061243404461921867259524102234794077679908807626808177544101720981909501025932414037494367
+pub const FINAL_EXPONENT_LAST_CHUNK_ABS_OF_W0: BigInt<7> = BigInt::new([
+    0x015ee53470b38a5f,
+    0x599b2603b389da05,
+    0x253d5aeb355d08a7,
+    0xb1c0c6eedbdd6695,
+    0xfec3f47057e81e16,
+    0xe39b78503370b17c,
+    0x16ffbfbec65bc2d7,
 ]);
 
 /// FINAL_EXPONENT_LAST_CHUNK_W1 =
-/// 600746357873917083993205832519317177466334726496174638989123538389804293458724181786507272967556
-pub const FINAL_EXPONENT_LAST_CHUNK_W1: BigInt<9> = BigInt::new([
-    0xed29225c5506d584,
-    0x4360c489b2a69d35,
-    0xd497d8292a77e57a,
-    0x2e5856438790676e,
-    0x4800000815ea74e0,
-    0x0,
-    0x0,
-    0x0,
-    0x0,
+/// 100378547652292899160422870778451961778662157592086072177866198128427986533489016746242956374473328960687429046305983440028617033421860
+pub const FINAL_EXPONENT_LAST_CHUNK_W1: BigInt<7> = BigInt::new([
+    0xfdf3ad2d94e49424,
+    0xef13481abd75f835,
+    0x70f254b18219f8d1,
+    0x7570e3f01010030a,
+    0xe13f5007f97c2d19,
+    0x922024106b644319,
+    0x235ab675dc72a663,
 ]);
@@ -1,7 +1,374 @@
-use crate::yafa_108::*;
+use crate::{
+    glv::{
+        tests::{
+            check_clear_cofactor, check_endomorphism, check_msm, check_mul, check_subgroup_check,
+            double_and_add,
+        },
+        EndomorphismConfig, LAMBDA,
+    },
+    hashing::tests::check_hash_to_curve,
+    yafa_108::*,
+};
 use ark_algebra_test_templates::*;
+use ark_ec::{
+    hashing::HashToCurve,
+    pairing::{Pairing, PairingOutput},
+    CurveGroup,
+};
+use ark_ff::{BigInt, BigInteger, CyclotomicMultSubgroup, Field, One, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{test_rng, vec::Vec, UniformRand, Zero};
+use sha2::{Sha256, Sha512};
 
 test_group!(g1; G1Projective; sw);
 test_group!(g2; G2Projective; sw);
 test_group!(pairing_output; ark_ec::pairing::PairingOutput<Yafa>; msm);
 test_pairing!(pairing; crate::yafa_108::Yafa);
+
+#[test]
+fn test_pairing_with_identity() {
+    let mut rng = test_rng();
+
+    for _ in 0..5 {
+        let p = G1Projective::rand(&mut rng);
+        let q = G2Projective::rand(&mut rng);
+
+        assert!(Yafa::pairing(G1Projective::zero(), q).is_zero());
+        assert!(Yafa::pairing(p, G2Projective::zero()).is_zero());
+        assert!(Yafa::pairing(G1Projective::zero(), G2Projective::zero()).is_zero());
+        assert!(Yafa::ate_pairing(&G1Projective::zero().into_affine(), &q.into_affine()).is_one());
+
+        // Pairs involving the identity are skipped by the multi-Miller loop.
+        assert_eq!(
+            Yafa::multi_pairing([p, G1Projective::zero(), p], [q, q, G2Projective::zero()]),
+            Yafa::pairing(p, q)
+        );
+    }
+}
+
+#[test]
+fn test_optimal_ate_matches_ate() {
+    // The optimal ate pairing equals the reference ate pairing raised to this power.
+    const EXPONENT: Fr = FrConfig::const_from_bigint(BigInt!(
+        "23815561003181697568789180894232522343027429538723015122662260316930973568464"
+    ));
+
+    let mut rng = test_rng();
+
+    for _ in 0..5 {
+        let p = G1Projective::rand(&mut rng).into_affine();
+        let q = G2Projective::rand(&mut rng).into_affine();
+
+        let ate = Yafa::ate_pairing(&p, &q);
+        assert_eq!(Yafa::pairing(p, q).0, ate.pow(EXPONENT.into_bigint()));
+    }
+}
+
+// The hard part (q^2 - q + 1) / r = HARD_PART_W0 + HARD_PART_W1 * q, without a multiplier.
+
+/// HARD_PART_W0 =
+/// 19533729308556160109794825562832707092134991522349430861538344636908041560785866966036323918363729219562215110665533950746637708295543215802259030565596621823690292271103113
+const HARD_PART_W0: BigInt<9> = BigInt::new([
+    0x7d919d468ad5bc89,
+    0x851c91204bcf98dc,
+    0x1ccd844b0de55ff8,
+    0x36bd1be87c106c82,
+    0xcfe6ae45c3d21c71,
+    0x96d3c4ddb45de345,
+    0xe20009f1de3c5d1f,
+    0xc534aab128f9dd7a,
+    0x1437eba2e55524f8,
+]);
+
+/// HARD_PART_W1 =
+/// 600746357873917083993205832519317177466334726496174638989123538389804293458724181786507272967556
+const HARD_PART_W1: BigInt<9> = BigInt::new([
+    0xed29225c5506d584,
+    0x4360c489b2a69d35,
+    0xd497d8292a77e57a,
+    0x2e5856438790676e,
+    0x4800000815ea74e0,
+    0x0,
+    0x0,
+    0x0,
+    0x0,
+]);
+
+#[test]
+fn test_final_exponentiation_matches_hard_part() {
+    let mut rng = test_rng();
+
+    for _ in 0..5 {
+        let p = G1Projective::rand(&mut rng);
+        let q = G2Projective::rand(&mut rng);
+        let f = Yafa::miller_loop(p, q).0;
+
+        // f^((q^3 - 1) * (q + 1) * (q^2 - q + 1) / r)
+        let elt = Yafa::final_exponentiation_first(&f, &f.inverse().unwrap());
+        let mut elt_q = elt;
+        elt_q.frobenius_map(1);
+        let expected = elt.cyclotomic_exp(HARD_PART_W0) * elt_q.cyclotomic_exp(HARD_PART_W1);
+
+        assert_eq!(
+            Yafa::pairing(p, q).0,
+            expected.pow(FINAL_EXPONENT_MULTIPLIER)
+        );
+    }
+}
+
+#[test]
+fn test_torus_serialization() {
+    let mut rng = test_rng();
+
+    for _ in 0..5 {
+        let g = Yafa::pairing(G1Projective::rand(&mut rng), G2Projective::rand(&mut rng));
+        let torus = TorusPairingOutput::from(g);
+
+        let mut bytes = Vec::new();
+        torus.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), torus.compressed_size());
+        assert_eq!(2 * bytes.len(), g.compressed_size());
+        assert_eq!(
+            TorusPairingOutput::deserialize_compressed(&bytes[..]).unwrap(),
+            torus
+        );
+    }
+
+    let one = TorusPairingOutput::from(PairingOutput::<Yafa>::zero());
+    assert!(one.compress().is_zero());
+    assert_eq!(TorusPairingOutput::decompress(&Fq3::zero()), one);
+
+    // Any a in Fq3 encodes an element of the torus T2(Fq3), but not necessarily of GT.
+    let a = Fq3::rand(&mut rng);
+    let mut bytes = Vec::new();
+    a.serialize_compressed(&mut bytes).unwrap();
+    assert!(TorusPairingOutput::deserialize_compressed(&bytes[..]).is_err());
+    let g = TorusPairingOutput::deserialize_compressed_unchecked(&bytes[..]).unwrap();
+    assert_eq!(g.compress(), a);
+}
+
+#[test]
+fn test_g1_endomorphism() {
+    check_endomorphism::<g1::Parameters, 2>();
+}
+
+#[test]
+fn test_g1_glv_mul() {
+    check_mul::<g1::Parameters, 2>();
+}
+
+#[test]
+fn test_g1_glv_msm() {
+    check_msm::<g1::Parameters, 2>();
+}
+
+#[test]
+fn test_g2_psi() {
+    let mut rng = test_rng();
+    let q = Fr::from_le_bytes_mod_order(&Fq::MODULUS.to_bytes_le());
+    assert_eq!(q, -LAMBDA);
+
+    for _ in 0..5 {
+        let p = G2Projective::rand(&mut rng);
+        let psi = g2::psi(&p.into_affine());
+        assert_eq!(psi, double_and_add(&p, &q).into_affine());
+        let phi = <g2::Parameters as EndomorphismConfig<2>>::endomorphism(&p);
+        assert_eq!(phi.into_affine(), -psi);
+    }
+}
+
+#[test]
+fn test_g2_gls_mul() {
+    check_mul::<g2::Parameters, 2>();
+}
+
+#[test]
+fn test_g2_gls_msm() {
+    check_msm::<g2::Parameters, 2>();
+}
+
+#[test]
+fn test_g1_clear_cofactor() {
+    check_clear_cofactor::<g1::Parameters>();
+}
+
+#[test]
+fn test_g2_clear_cofactor() {
+    check_clear_cofactor::<g2::Parameters>();
+}
+
+#[test]
+fn test_g1_subgroup_check() {
+    check_subgroup_check::<g1::Parameters>();
+}
+
+#[test]
+fn test_g2_subgroup_check() {
+    check_subgroup_check::<g2::Parameters>();
+}
+
+#[test]
+fn test_g1_hash_to_curve_sha256() {
+    check_hash_to_curve::<g1::Parameters, Sha256, 4>(
+        G1_SHA256_SUITE_ID,
+        &[
+            [
+                "27417662461572086346504736144573551324179178608208087271757934617632845042106579952086628986184505266237092224174314777205044722851433816566586431050867295062695884820997788",
+                "13415435118345119735871683168390131683247417360604311004660744348180537813594168355104832788576994949364174070644939443337837456854521574640189472097776855659187407318566405",
+                "3729520501242115484855973476837062198366586952683260354023938167679396218806018078946309921045639242237973925407374781900078805478590110514733372929489736111425856990603783",
+                "19603445168245527734892687088197866011798757125267815910965305605799431525603874977407941530112132099287330883466361927661374566121252605602693625843611194433363695108880255",
+            ],
+            [
+                "13507107083933353075536892127743071049911807516959700656295406054180719561317746018576750761226926331329771707839370537602782346562463240348091989493205828243630599381802263",
+                "26903894949711432781307770282790737891544982898620139900602096202556369472875192537107207937916849345385077146213029744481846379905719299396718062617016632983563598977120313",
+                "8389118164175460373187347551345794066174969483791188639407955277712630570770593301225728763141170020379699302088021722116293262632048019365116063117574968833807222005643573",
+                "23637283196277129329008798650875334948065511744790830160154346898639004881304704211599609748786524565728854158554679131581490202983415921921605378286502190295053631592508395",
+            ],
+            [
+                "13929690609711138536116364660493667707690360038845023190564886272396376325631061937111925287535630615948115483213161153009321946511594143181551734768094317336559048083945241",
+                "14005209615771492522631803203664195997151139092278937980530742379853602104944167825720712658203571690229086103294931444781887211093704598419035802922792364093876961422052762",
+                "31735748323315149988334862452663549409414595701541274392705384953573669047462508161634823212580718090371802994654182398998938353409067360458913458497563517772201904506271247",
+                "6202096542424430612398221149463544741177939429016333669860046636915221096794545720808179748762001408035132775772760498686013223342507716736627281115602741295451032651017490",
+            ],
+            [
+                "29047923855011850336941830802543916279422856877137450517643873041739491765516840587202511151286121026212976400011291062601092823785633360932176108400574336120243638788055971",
+                "18158305859431406599560965966026531518996060934542518528751336272950031645021529811174886951216811543081765938602855170956630114443442631151819398300561562603995081671503110",
+                "8731122408487038895690513705902978625218425403093941176051356352835838782273801264070972932780443616650319038192823112553042832436328330824039183741326439005043458252876482",
+                "30583625196586138733649582258079980451670893427665360701847465218484483535765010534032133379706272313433256231169970618092718775500351398598737153055318996334167962013607347",
+            ],
+            [
+                "545517786425189420369082592682759748700788767906479504504611719619169601401397432381316510216775825308180418103484849116960719026285003783474219976007158491207399498224570",
+                "5575089739053560099242440130361505812083464964581373785609226950230198401686327613301638505740792350125506969665325875590445490003049498724197377235646998208992116630015449",
+                "30486463063491266652984740876454301951975304112077498679589700395272823589219753980345599487676748299111400645778647615840272101979579451151294220431948898091595980496387867",
+                "2299576548242238051278226330631553289249107500985763763409747018378530309115022409455579009683536994303471969477790144891248935579289697836574374362033630668663723139157987",
+            ],
+        ],
+    );
+}
+
+#[test]
+fn test_g1_hash_to_curve_sha512() {
+    check_hash_to_curve::<g1::Parameters, Sha512, 4>(
+        G1_SHA512_SUITE_ID,
+        &[
+            [
+                "17055777451732512759337233996594898604947505648835926098590404133596337157898894526242249958582021522822913765668017135853732533394392739063575850538387281062059912925098932",
+                "29693758150655674622012599750885451978305376705764199574100266402852655890723160902640378547895420546150283653462964373927889397090457532630494838599156875339217630433817498",
+                "21122678028179439615246698363466762875940563547912130380292332677566366723506505031736607090564367508369179903550147287741166859566036835236292231929827431116560939487269926",
+                "25845270876383406533768572822754597785276238496083181141888105931448408091366952954717955359524999863395045614646010777870034385304719492687727744870216832716051991809934267",
+            ],
+            [
+                "32373724327284708623982721113116800828461108224741309658075153523986680616896740960339757561122817862261609528572157640335213786497951400465539285262436669436231002550458890",
+                "29897323563870708759737714787320242614802705861117833408784561320128191042092988849496067050976359046161479460507845580964933304734606445107736436485521700723562760181918299",
+                "16680614857576506193913863428610463380230047437925839614040800011848221015476601571763604305622016935150367281851907050875197652563912792371490810838424390990047027674640023",
+                "8288787505107411606195399245268595951350245215751489666751014246481903534400510459216227102491849282555051074562259924847628263255450412007251176365924681237699251264931971",
+            ],
+            [
+                "21708655666914048304237741537777104012161005387669879207572492882662061823664823203192652340587814822249526275378147341118421903654608084272553628401776952103697608309052047",
+                "27132855231363944195391022898041364855336369430249096600077994049342539465830175329790699158150982591096373528623383330609609665997578981059586433347193830088107372780329874",
+                "602493525471850753919968157512937583559770980017701804459751727557729545727201872155189023031072603452503594942963668639349756294699674203327916947268551984002544483071984",
+                "22395936948175224849096401551287039490065729112783861343975275202737178936828610621666442727189429650661256801875800430490440737079685316869574160924873191385764058983800688",
+            ],
+        ],
+    );
+}
+
+#[test]
+fn test_g1_hash_to_curve_dst() {
+    let p = G1Sha256Hasher::new(b"DST-1").unwrap().hash(b"msg").unwrap();
+    assert_ne!(
+        p,
+        G1Sha256Hasher::new(b"DST-2").unwrap().hash(b"msg").unwrap()
+    );
+    assert_ne!(
+        p,
+        G1Sha512Hasher::new(b"DST-1").unwrap().hash(b"msg").unwrap()
+    );
+    assert_eq!(
+        p,
+        G1Sha256Hasher::new(b"DST-1").unwrap().hash(b"msg").unwrap()
+    );
+}
+
+#[test]
+fn test_g2_hash_to_curve_sha256() {
+    check_hash_to_curve::<g2::Parameters, Sha256, 4>(
+        G2_SHA256_SUITE_ID,
+        &[
+            [
+                "20198579637700128857317911800348333763019590669547862893341049266972941980228619170109638404664905530101099332247770936834251543609152378091163280893048478373912482329108979",
+                "15316184593188462761155545302746824405717589845103813130673703213999691533240908909390210999858867278142128880361450236600150990920746954622389130511748440957540042080419912",
+                "31553844391210222077945740867227097195877206221185840858678042238517484356036327051958933181309499978769716151281125481038545755505339853759488040256376801755664176488895416",
+                "29272185221378534464906165143671998395884379157084399892075250539893055644770008971542935844530125657772258532661989039365775464185327449054521795732788695283636191748110371",
+            ],
+            [
+                "2486152914714425109920645749094842765681146855921726449454563175102118267776913205829823487237932207101439402026381712947575281466793516450614674961967986342652046620299744",
+                "18767758718286876962308407775816177216756815925201429898303179151985765999280635944522477173010045433207024214879405222700257081631450212445057523865550397260747869372279209",
+                "4737965888293217273193981195124607888404785223918653022255922589285825162978886816347231003656323369545553383902012201335431876016586241222764181890396710111255158228960089",
+                "33907946281174358024873513186316576382696011094362328840301175420915537588892052859805593596240610103159861710324209774982379600237684600740341253395891642308051293577994107",
+            ],
+            [
+                "4838678573713785704218912347651878712128320318774805548155569661078845044564479153181966739280260516413977474840389383369048252997742305824189881656845946900269467844399415",
+                "25740710525354265542970835341409267459057236717301521527324686147557575611394910786076482432332761368276318091086341366441594673407567394782284116109325263088282452280790243",
+                "20382510562534246484400644797123655803526953894198830259601364504672409268458808401048575667223876223903128494507857645842671531419578890092184644160144972209900221370054611",
+                "16919267274311438832323435164258850141610540054118862779634326972201237322551162092463864276805438260779713793962582632446231719791601018699533624684268371709201083123613109",
+            ],
+            [
+                "2043572703938102165751140959209100678250350535004329894805213819207921431994299763296013602297691635906778613018461977340841832239670464415479449351799930570544372186099189",
+                "34423868722397243392777882908549149788809956895876921276230266040193567450089351498326603600150183777903820255821959597369307724416233834772689920105616735605878322222874602",
+                "15358293824557091663838584567245590255792740213708320189752003907367329373801161889971576454823156697073425993252097720265420837810199424817649350575207385573243414176848588",
+                "14220201619654420772807348713255645182499930376088066757350761533738191656196789882919498545157746243076814242614838087464390244649421674975343034832376272928837027202655397",
+            ],
+            [
+                "11483460787231241802686505070730679071016634207300499384498253977367636631095946038830831193908524436381594051922172806873697750690654288368318451179404992317960139626284966",
+                "406149115901087384759442589577433393236254555293477760629027627706436209558331041607420045908636483863767969341436321877239234383060125458286345311077262692821439642307693",
+                "8596404623091328409836792862718810093349616198382101282704036648927374053657697520368720744603884180609993788419250506145818997817537559424176546500302561658093738673720884",
+                "5705159207661833179041919718889537542915494233878597220204199304955820554536295185920778781446613834912602477206146709808651622145263238504924539156096863019796094112863978",
+            ],
+        ],
+    );
+}
+
+#[test]
+fn test_g2_hash_to_curve_sha512() {
+    check_hash_to_curve::<g2::Parameters, Sha512, 4>(
+        G2_SHA512_SUITE_ID,
+        &[
+            [
+                "27408251245519415545941008614699719168730535005602649676256847468378372302691587440725677887005258260158245213732563723744406353676613224286026432821640797008013228661199928",
+                "9446675883102972004963938911715371520626280739431344792215381193597144161259106507461539045514155991142605466801258070452696089657602403220701584206111936031675427942975714",
+                "14885400499547986385597140568242131414420871292730048260884812464988855854109032873709090735783059735444538821600510029637064546142756862470072831989980323090103356312593436",
+                "23352255173382663905695422787020118644680890860397522423684112779699191942705948686627179652044409973865579558669460599802230955427486384416520149396681587555935161510331627",
+            ],
+            [
+                "29583991930122678675037909605722170490371051847310318589153001940222549123466246369223099056840519483553221174140991511873105221432464493394515398200097653822949422851915174",
+                "20069704505315763916412597586376910139568906924803507324749272672247814833962181324190492000448861843849697149334242828835711269555591714944943345724498507942316958999875894",
+                "15703675671477885580071415659365783531655860364880948888216556502593249186875408501683188910136746588409218156008927155221925701279651183812953892789076775437736074649218951",
+                "20104430923868764064654651271628331825544680774890621800990153832435914865684485874690286629671154975974067928311091760469024495617050535782969700561973781900215387426836233",
+            ],
+            [
+                "7289691623007530570941751788461655960729575420398952034776321993961481886059797427339838045678739540303686963893592169815140419883203940862868889864900489749382008397414848",
+                "25258813722548748751644055817316842069439829021568216023618411479649328430055631449694519995098556306603415113761317153540729276765162858738905123697562441121113261448996580",
+                "24732001553060457902407945787753096117740996584155336358329825489185171653551804391866209222511538818810381737952788764010326219186613736028162241326121032479613697034468343",
+                "21892684748976422022735832212469514570946401275680346712007605398934603998991118103411663614065100504699308431971762889916319333713143730741727224522618806452828860764400802",
+            ],
+        ],
+    );
+}
+
+#[test]
+fn test_g2_hash_to_curve_dst() {
+    let p = G2Sha256Hasher::new(b"DST-1").unwrap().hash(b"msg").unwrap();
+    assert_ne!(
+        p,
+        G2Sha256Hasher::new(b"DST-2").unwrap().hash(b"msg").unwrap()
+    );
+    assert_ne!(
+        p,
+        G2Sha512Hasher::new(b"DST-1").unwrap().hash(b"msg").unwrap()
+    );
+    assert_eq!(
+        p,
+        G2Sha256Hasher::new(b"DST-1").unwrap().hash(b"msg").unwrap()
+    );
+}
@@ -0,0 +1,92 @@
+use ark_ec::pairing::PairingOutput;
+use ark_ff::{fields::fp6_2over3::Fp6Config, Field, One, PrimeField, Zero};
+use ark_serialize::*;
+
+use crate::yafa_108::{Fq3, Fq6, Fq6Config, Fr, Yafa};
+
+/// A pairing output that is serialized in the torus T2(Fq3), at half the size of an element of
+/// Fq6.
+///
+/// Every element of GT has norm 1 over Fq3, so for g = c0 + c1 * Y it holds that
+/// g = (a + Y) / (a - Y) with a = (1 + c0) / c1, and only a is serialized. This fails for
+/// c1 = 0, that is, for g = 1 and g = -1. The latter is not in GT, so g = 1 is encoded as a = 0.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TorusPairingOutput(pub PairingOutput<Yafa>);
+
+impl TorusPairingOutput {
+    /// Returns the torus coordinate a of the pairing output, which must be in GT.
+    pub fn compress(&self) -> Fq3 {
+        let g = &(self.0).0;
+        g.c1.inverse()
+            .map(|c1_inv| (g.c0 + &Fq3::one()) * &c1_inv)
+            .unwrap_or_else(Fq3::zero)
+    }
+
+    /// Recovers the element (a + Y) / (a - Y) of the torus T2(Fq3), or 1 if a = 0.
+    ///
+    /// The result is only in GT if `a` is the output of [`Self::compress`].
+    pub fn decompress(a: &Fq3) -> Self {
+        if a.is_zero() {
+            return Self(PairingOutput(Fq6::one()));
+        }
+
+        // (a + Y) / (a - Y) = ((a^2 + Y^2) + 2 * a * Y) / (a^2 - Y^2), where a^2 - Y^2 is not
+        // zero because Y^2 = u is not a square in Fq3.
+        let a_square = a.square();
+        let denom_inv = (a_square - &Fq6Config::NONRESIDUE).inverse().unwrap();
+        let c0 = (a_square + &Fq6Config::NONRESIDUE) * &denom_inv;
+        let c1 = a.double() * &denom_inv;
+        Self(PairingOutput(Fq6::new(c0, c1)))
+    }
+}
+
+impl From<PairingOutput<Yafa>> for TorusPairingOutput {
+    fn from(g: PairingOutput<Yafa>) -> Self {
+        Self(g)
+    }
+}
+
+impl From<TorusPairingOutput> for PairingOutput<Yafa> {
+    fn from(g: TorusPairingOutput) -> Self {
+        g.0
+    }
+}
+
+impl CanonicalSerialize for TorusPairingOutput {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.compress().serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        Fq3::zero().serialized_size(compress)
+    }
+}
+
+impl Valid for TorusPairingOutput {
+    fn check(&self) -> Result<(), SerializationError> {
+        // GT is the only subgroup of order r of the cyclic group Fq6^*.
+        match (self.0).0.pow(Fr::MODULUS).is_one() {
+            true => Ok(()),
+            false => Err(SerializationError::InvalidData),
+        }
+    }
+}
+
+impl CanonicalDeserialize for TorusPairingOutput {
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let a = Fq3::deserialize_with_mode(reader, compress, validate)?;
+        let g = Self::decompress(&a);
+        if let Validate::Yes = validate {
+            g.check()?;
+        }
+        Ok(g)
+    }
+}
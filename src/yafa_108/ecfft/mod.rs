@@ -0,0 +1,535 @@
+//! An elliptic curve FFT (ECFFT) domain over the scalar field of Yafa-108, following
+//! Ben-Sasson, Carmon, Kopparty and Levit, "Elliptic Curve Fast Fourier Transform (ECFFT)
+//! Part I: Fast Polynomial Algorithms over all Finite Fields".
+//!
+//! The scalar field 2^255 - 19 has a two-adicity of 2, so it has no large multiplicative
+//! subgroups of order 2^k to run a radix-2 FFT on. Instead, the evaluation points are the
+//! x-coordinates of a coset `Q + <G>` of a cyclic subgroup of order 2^21 on the auxiliary curve
+//! y^2 = x^3 + 1173 x^2 + 104 x over Fr, and the role of squaring is played by a chain of
+//! 2-isogenies, each of which maps the coset two-to-one onto a coset of half the size.
+//!
+//! Let U_0 be the x-coordinates of Q + j * G_M for j < M, where G_M has order M, and let
+//! U_{l+1} be the image of the first half of U_l under the l-th isogeny. A set is a progression
+//! {U_l[r + s * j]} in one of these layers, and it maps two-to-one onto the progression with the
+//! same offset and stride in the next layer.
+//!
+//! EXIT is not implemented with the O(n log^2 n) bound of the paper: [`EcfftDomain::ifft`]
+//! recombines the halves of each level in coefficient form with the Toom-Cook products of
+//! [`crate::yafa_108::poly`], and so takes O(n^1.47) time. The subproduct trees of that module
+//! would not help, as they interpolate with the same products in O(n^1.47 log n) time. ENTER,
+//! EXTEND and low-degree extensions do not go through EXIT and keep their bounds.
+
+use ark_ff::{batch_inversion, BigInt, Field, One, Zero};
+use ark_std::{vec, vec::Vec};
+
+use crate::yafa_108::{poly::mul, Fr, FrConfig};
+
+#[cfg(test)]
+mod tests;
+
+/// COEFF_A = 1173, the coefficient of x^2 in the auxiliary curve y^2 = x^3 + a x^2 + b x.
+const COEFF_A: Fr = FrConfig::const_from_bigint(BigInt!("1173"));
+
+/// COEFF_B = 104, the coefficient of x in the auxiliary curve y^2 = x^3 + a x^2 + b x.
+const COEFF_B: Fr = FrConfig::const_from_bigint(BigInt!("104"));
+
+/// GENERATOR_X =
+/// 48581887230273080506816272252642460908729056500626091436758243195765699761662
+///
+/// The point G of order 2^21 on the auxiliary curve, which generates its 2-Sylow subgroup.
+const GENERATOR_X: Fr = FrConfig::const_from_bigint(BigInt!(
+    "48581887230273080506816272252642460908729056500626091436758243195765699761662"
+));
+
+/// GENERATOR_Y =
+/// 39161042427311360019600035516739830171457136362155114054638734692248860116076
+const GENERATOR_Y: Fr = FrConfig::const_from_bigint(BigInt!(
+    "39161042427311360019600035516739830171457136362155114054638734692248860116076"
+));
+
+/// OFFSET_X = 1
+///
+/// The point Q, whose order is not a power of 2. Hence the coset Q + <G> avoids the identity
+/// and the 2-torsion point (0, 0), and its x-coordinates are pairwise distinct.
+const OFFSET_X: Fr = FrConfig::const_from_bigint(BigInt!("1"));
+
+/// OFFSET_Y =
+/// 285771673761424465065572686066693001030524838120295651740624472484330344498
+const OFFSET_Y: Fr = FrConfig::const_from_bigint(BigInt!(
+    "285771673761424465065572686066693001030524838120295651740624472484330344498"
+));
+
+/// An evaluation domain of size 2^k over [`Fr`], for polynomials of degree less than 2^k.
+///
+/// The domain comes with an extended domain of `blowup` times its size, for low-degree
+/// extensions. Element `i` of the domain is element `i * blowup` of the extended domain.
+///
+/// Precomputation takes O(n log n) space, plus the time of the polynomial products in
+/// [`Self::ifft`].
+#[derive(Clone, Debug)]
+pub struct EcfftDomain {
+    size: usize,
+    blowup: usize,
+    isogenies: Vec<Isogeny>,
+    /// `cosets[r]` is the set of extended domain elements `r + blowup * j`, and `cosets[0]` is
+    /// the domain itself.
+    cosets: Vec<Coset>,
+    /// `halves[i]` splits the set of domain elements `2^i * j` into even and odd `j`.
+    halves: Vec<Half>,
+}
+
+impl EcfftDomain {
+    /// The maximal log2 of the size of an extended domain.
+    pub const MAX_LOG_SIZE: usize = 21;
+
+    /// Returns a domain for polynomials with `num_coeffs` coefficients, whose size is the next
+    /// power of two, or `None` if it is too large.
+    pub fn new(num_coeffs: usize) -> Option<Self> {
+        Self::new_with_blowup(num_coeffs, 1)
+    }
+
+    /// Returns a domain for polynomials with `num_coeffs` coefficients, with an extended domain
+    /// `blowup` times its size, or `None` if `blowup` is not a power of two or the extended
+    /// domain is too large.
+    pub fn new_with_blowup(num_coeffs: usize, blowup: usize) -> Option<Self> {
+        let size = num_coeffs.checked_next_power_of_two()?;
+        if !blowup.is_power_of_two() {
+            return None;
+        }
+        let log_size = size.trailing_zeros() as usize;
+        let log_extended_size = size.checked_mul(blowup)?.trailing_zeros() as usize;
+        if log_extended_size > Self::MAX_LOG_SIZE {
+            return None;
+        }
+
+        let generator = (log_extended_size.max(1)..Self::MAX_LOG_SIZE)
+            .fold((GENERATOR_X, GENERATOR_Y), |point, _| double(point));
+        let isogenies = isogeny_chain(generator, log_extended_size, log_size);
+
+        let mut universe = vec![coset_points(generator, log_extended_size)];
+        for isogeny in &isogenies {
+            let layer = universe.last().unwrap();
+            let next = isogeny.evaluate_batch(&layer[..layer.len() / 2]);
+            universe.push(next);
+        }
+
+        let cosets = (0..blowup)
+            .map(|offset| Coset::new(&universe, blowup, offset))
+            .collect();
+        let halves = (0..log_size)
+            .map(|i| Half::new(&universe, blowup << (i + 1), blowup << i))
+            .collect();
+
+        Some(Self {
+            size,
+            blowup,
+            isogenies,
+            cosets,
+            halves,
+        })
+    }
+
+    /// Returns the size of the domain.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the ratio of the sizes of the extended domain and the domain.
+    pub fn blowup(&self) -> usize {
+        self.blowup
+    }
+
+    /// Returns the i-th element of the domain.
+    pub fn element(&self, i: usize) -> Fr {
+        self.cosets[0].levels[0].points[i]
+    }
+
+    /// Returns the elements of the domain.
+    pub fn elements(&self) -> &[Fr] {
+        &self.cosets[0].levels[0].points
+    }
+
+    /// Returns the size of the extended domain.
+    pub fn extended_size(&self) -> usize {
+        self.size * self.blowup
+    }
+
+    /// Returns the i-th element of the extended domain.
+    pub fn extended_element(&self, i: usize) -> Fr {
+        self.cosets[i % self.blowup].levels[0].points[i / self.blowup]
+    }
+
+    /// Evaluates the polynomial with the given coefficients on the domain (ENTER), in
+    /// O(n log^2 n) time.
+    pub fn fft(&self, coeffs: &[Fr]) -> Vec<Fr> {
+        assert!(coeffs.len() <= self.size, "too many coefficients");
+        let mut coeffs = coeffs.to_vec();
+        coeffs.resize(self.size, Fr::zero());
+        self.enter(&coeffs, 0)
+    }
+
+    /// Interpolates the polynomial of degree less than the size of the domain with the given
+    /// evaluations on the domain (EXIT).
+    ///
+    /// This takes O(n^1.47) time rather than the O(n log^2 n) of the paper, since recombining
+    /// the halves multiplies by the vanishing polynomials of the subdomains with Toom-Cook
+    /// multiplication. See the [module documentation](self).
+    pub fn ifft(&self, evals: &[Fr]) -> Vec<Fr> {
+        assert_eq!(evals.len(), self.size, "wrong number of evaluations");
+        self.exit(evals, 0)
+    }
+
+    /// Evaluates the polynomial of degree less than the size of the domain with the given
+    /// evaluations on the domain at the extended domain elements `coset + blowup * j` (EXTEND),
+    /// in O(n log n) time.
+    pub fn extend(&self, evals: &[Fr], coset: usize) -> Vec<Fr> {
+        assert_eq!(evals.len(), self.size, "wrong number of evaluations");
+        extend(&self.cosets[0], &self.cosets[coset], evals, 0)
+    }
+
+    /// Evaluates the polynomial of degree less than the size of the domain with the given
+    /// evaluations on the domain at all the elements of the extended domain.
+    pub fn low_degree_extension(&self, evals: &[Fr]) -> Vec<Fr> {
+        assert_eq!(evals.len(), self.size, "wrong number of evaluations");
+        let mut extended = vec![Fr::zero(); self.extended_size()];
+        for (j, eval) in evals.iter().enumerate() {
+            extended[j * self.blowup] = *eval;
+        }
+        for coset in 1..self.blowup {
+            for (j, eval) in self.extend(evals, coset).into_iter().enumerate() {
+                extended[coset + j * self.blowup] = eval;
+            }
+        }
+        extended
+    }
+
+    /// Evaluates the vanishing polynomial of the domain at tau, in O(log n) time.
+    pub fn evaluate_vanishing_polynomial(&self, tau: Fr) -> Fr {
+        // A pair of elements s, s' of a set over t satisfies (x - s) (x - s') = x (psi(x) - t),
+        // so the vanishing polynomial of the set at x is x^(n / 2) times the vanishing
+        // polynomial of its image at psi(x). At x = 0, the pair contributes s * s' = b.
+        let levels = &self.cosets[0].levels;
+        let mut tau = tau;
+        let mut result = Fr::one();
+        for (level, isogeny) in levels.iter().zip(&self.isogenies) {
+            let exponent = [(level.points.len() / 2) as u64];
+            if tau.is_zero() {
+                return result * isogeny.b.pow(exponent);
+            }
+            result *= tau.pow(exponent);
+            tau = isogeny.evaluate(tau);
+        }
+        result * (tau - levels.last().unwrap().points[0])
+    }
+
+    /// Evaluates the polynomial with the given coefficients on the set of domain elements
+    /// `2^i * j`.
+    fn enter(&self, coeffs: &[Fr], i: usize) -> Vec<Fr> {
+        let n = coeffs.len();
+        if n == 1 {
+            return coeffs.to_vec();
+        }
+
+        // Split the polynomial as lo + x^(n / 2) * hi, evaluate both halves on the even
+        // elements, and extend them to the odd elements.
+        let half = &self.halves[i];
+        let (lo, hi) = coeffs.split_at(n / 2);
+        let lo_even = self.enter(lo, i + 1);
+        let hi_even = self.enter(hi, i + 1);
+        let lo_odd = extend(&half.even, &half.odd, &lo_even, 0);
+        let hi_odd = extend(&half.even, &half.odd, &hi_even, 0);
+
+        let mut evals = Vec::with_capacity(n);
+        for j in 0..n / 2 {
+            evals.push(lo_even[j] + half.even_powers[j] * hi_even[j]);
+            evals.push(lo_odd[j] + half.odd_powers[j] * hi_odd[j]);
+        }
+        evals
+    }
+
+    /// Interpolates the polynomial with the given evaluations on the set of domain elements
+    /// `2^i * j`.
+    fn exit(&self, evals: &[Fr], i: usize) -> Vec<Fr> {
+        let n = evals.len();
+        if n == 1 {
+            return evals.to_vec();
+        }
+
+        // Split the polynomial as a * z + b, where z vanishes on the even elements and a and b
+        // have degree less than n / 2. Then b agrees with the polynomial on the even elements,
+        // which gives a on the odd elements.
+        let half = &self.halves[i];
+        let b_even: Vec<Fr> = evals.iter().step_by(2).copied().collect();
+        let b_odd = extend(&half.even, &half.odd, &b_even, 0);
+        let a_odd: Vec<Fr> = evals
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .zip(b_odd)
+            .zip(&half.vanishing_inverses)
+            .map(|((eval, b), z)| (*eval - b) * z)
+            .collect();
+        let a_even = extend(&half.odd, &half.even, &a_odd, 0);
+
+        let a = self.exit(&a_even, i + 1);
+        let b = self.exit(&b_even, i + 1);
+
+        // The vanishing polynomial is monic, so a * z = a * (z - x^(n / 2)) + x^(n / 2) * a.
+        let mut coeffs = mul(&a, &half.vanishing);
+        coeffs.push(Fr::zero());
+        for (c, b) in coeffs.iter_mut().zip(&b) {
+            *c += b;
+        }
+        for (c, a) in coeffs[n / 2..].iter_mut().zip(&a) {
+            *c += a;
+        }
+        coeffs
+    }
+}
+
+/// A point on the auxiliary curve.
+type Point = (Fr, Fr);
+
+/// Doubles a point of the auxiliary curve other than (0, 0).
+fn double((x, y): Point) -> Point {
+    let lambda = (x.square() * Fr::from(3u64) + COEFF_A.double() * x + COEFF_B)
+        * y.double().inverse().unwrap();
+    let x3 = lambda.square() - COEFF_A - x.double();
+    (x3, lambda * (x - x3) - y)
+}
+
+/// Adds two points of the auxiliary curve, given the inverse of the difference of their
+/// x-coordinates.
+fn add((x1, y1): Point, (x2, y2): Point, difference_inverse: Fr) -> Point {
+    let lambda = (y2 - y1) * difference_inverse;
+    let x3 = lambda.square() - COEFF_A - x1 - x2;
+    (x3, lambda * (x1 - x3) - y1)
+}
+
+/// Returns the x-coordinates of Q + j * G for j < 2^log_order, where G has order 2^log_order.
+fn coset_points(generator: Point, log_order: usize) -> Vec<Fr> {
+    let mut points = vec![(OFFSET_X, OFFSET_Y)];
+    let mut step = generator;
+    for i in 0..log_order {
+        // The coset avoids the subgroup, so no two points have the same x-coordinate.
+        let mut inverses: Vec<Fr> = points.iter().map(|point| step.0 - point.0).collect();
+        batch_inversion(&mut inverses);
+        let shifted: Vec<Point> = points
+            .iter()
+            .zip(inverses)
+            .map(|(point, inverse)| add(*point, step, inverse))
+            .collect();
+        points.extend(shifted);
+        if i + 1 < log_order {
+            step = double(step);
+        }
+    }
+    points.into_iter().map(|point| point.0).collect()
+}
+
+/// The x-coordinate map of a 2-isogeny with kernel (0, 0) from a curve
+/// y^2 = x^3 + a x^2 + b x, followed by a translation, which is x -> x + c + b / x.
+#[derive(Clone, Copy, Debug)]
+struct Isogeny {
+    c: Fr,
+    b: Fr,
+}
+
+impl Isogeny {
+    fn evaluate(&self, x: Fr) -> Fr {
+        x + self.c + self.b * x.inverse().unwrap()
+    }
+
+    fn evaluate_batch(&self, xs: &[Fr]) -> Vec<Fr> {
+        let mut inverses = xs.to_vec();
+        batch_inversion(&mut inverses);
+        xs.iter()
+            .zip(inverses)
+            .map(|(x, inverse)| *x + self.c + self.b * inverse)
+            .collect()
+    }
+}
+
+/// Returns the first `len` isogenies of the chain which halves the subgroup generated by a
+/// point of order 2^log_order on the auxiliary curve.
+fn isogeny_chain(generator: Point, log_order: usize, len: usize) -> Vec<Isogeny> {
+    // xs[i] is the x-coordinate of the image of 2^i * G on the current curve, which has order
+    // 2^(log_order - i - level). The kernel of the next isogeny is the point of order 2, which
+    // is (0, 0), and the translation takes the image of the point of order 4 to (0, 0).
+    let mut xs = Vec::with_capacity(log_order);
+    let mut point = generator;
+    for i in 0..log_order {
+        xs.push(point.0);
+        if i + 1 < log_order {
+            point = double(point);
+        }
+    }
+
+    let (mut a, mut b) = (COEFF_A, COEFF_B);
+    let mut chain = Vec::with_capacity(len);
+    for level in 0..len {
+        debug_assert!(xs[log_order - 1 - level].is_zero());
+        let isogeny = Isogeny { c: a, b };
+        let shift = match (log_order - 1 - level).checked_sub(1) {
+            Some(i) => isogeny.evaluate(xs[i]),
+            None => Fr::zero(),
+        };
+        for x in &mut xs[..log_order - 1 - level] {
+            *x = isogeny.evaluate(*x) - shift;
+        }
+        chain.push(Isogeny { c: a - shift, b });
+
+        // The image curve is y^2 = x^3 - 2a x^2 + (a^2 - 4b) x, translated by the shift.
+        let (a1, b1) = (-a.double(), a.square() - b.double().double());
+        a = shift * Fr::from(3u64) + a1;
+        b = shift.square() * Fr::from(3u64) + a1.double() * shift + b1;
+    }
+    chain
+}
+
+/// A set {U_0[offset + stride * j]} and its images under the isogeny chain, each of half the
+/// size of the previous one, down to a single point.
+#[derive(Clone, Debug)]
+struct Coset {
+    levels: Vec<CosetLevel>,
+}
+
+#[derive(Clone, Debug)]
+struct CosetLevel {
+    points: Vec<Fr>,
+    /// The weight x^(n / 2 - 1) of each point, where n is the size of the set.
+    weights: Vec<Fr>,
+    weight_inverses: Vec<Fr>,
+    /// The inverses of s' - s for the points s and s' with the same image, which are the
+    /// points j and j + n / 2.
+    difference_inverses: Vec<Fr>,
+}
+
+impl Coset {
+    fn new(universe: &[Vec<Fr>], stride: usize, offset: usize) -> Self {
+        let levels = universe
+            .iter()
+            .take_while(|layer| layer.len() >= stride)
+            .map(|layer| {
+                let points: Vec<Fr> = layer[offset..].iter().step_by(stride).copied().collect();
+                let n = points.len();
+                let exponent = [(n / 2).saturating_sub(1) as u64];
+                let weights: Vec<Fr> = points.iter().map(|x| x.pow(exponent)).collect();
+                let mut weight_inverses = weights.clone();
+                batch_inversion(&mut weight_inverses);
+                let mut difference_inverses: Vec<Fr> =
+                    (0..n / 2).map(|j| points[j + n / 2] - points[j]).collect();
+                batch_inversion(&mut difference_inverses);
+                CosetLevel {
+                    points,
+                    weights,
+                    weight_inverses,
+                    difference_inverses,
+                }
+            })
+            .collect();
+        Self { levels }
+    }
+}
+
+/// The split of a set of domain elements into the elements of even and odd index.
+#[derive(Clone, Debug)]
+struct Half {
+    even: Coset,
+    odd: Coset,
+    /// The n-th powers of the even elements, where n is the number of even elements.
+    even_powers: Vec<Fr>,
+    /// The n-th powers of the odd elements.
+    odd_powers: Vec<Fr>,
+    /// The coefficients of the vanishing polynomial of the even elements, without its
+    /// leading 1.
+    vanishing: Vec<Fr>,
+    /// The inverses of the vanishing polynomial of the even elements at the odd elements.
+    vanishing_inverses: Vec<Fr>,
+}
+
+impl Half {
+    fn new(universe: &[Vec<Fr>], stride: usize, odd_offset: usize) -> Self {
+        let even = Coset::new(universe, stride, 0);
+        let odd = Coset::new(universe, stride, odd_offset);
+        let (even_points, odd_points) = (&even.levels[0].points, &odd.levels[0].points);
+        let exponent = [even_points.len() as u64];
+        let even_powers = even_points.iter().map(|x| x.pow(exponent)).collect();
+        let odd_powers = odd_points.iter().map(|x| x.pow(exponent)).collect();
+        let vanishing = vanishing_polynomial(even_points);
+
+        // Going up the chain, the vanishing polynomial of a set of size n at x is
+        // x^(n / 2) = x * weight times the vanishing polynomial of its image at psi(x).
+        let top = even.levels.len() - 1;
+        let mut vanishing_inverses: Vec<Fr> = odd.levels[top]
+            .points
+            .iter()
+            .map(|x| *x - even.levels[top].points[0])
+            .collect();
+        for level in odd.levels[..top].iter().rev() {
+            let n = level.points.len();
+            vanishing_inverses = (0..n)
+                .map(|j| level.points[j] * level.weights[j] * vanishing_inverses[j % (n / 2)])
+                .collect();
+        }
+        batch_inversion(&mut vanishing_inverses);
+
+        Self {
+            even,
+            odd,
+            even_powers,
+            odd_powers,
+            vanishing,
+            vanishing_inverses,
+        }
+    }
+}
+
+/// Evaluates the polynomial of degree less than n with the given evaluations on the set `from`
+/// of size n on the set `to` at the same level of the chain.
+fn extend(from: &Coset, to: &Coset, evals: &[Fr], level: usize) -> Vec<Fr> {
+    let n = evals.len();
+    if n == 1 {
+        return evals.to_vec();
+    }
+
+    // Write the polynomial as w(x) * (p0(psi(x)) + x * p1(psi(x))) with w(x) = x^(n / 2 - 1)
+    // and p0, p1 of degree less than n / 2, and solve for p0 and p1 on the image of `from`
+    // from the values at each pair of points with the same image.
+    let m = n / 2;
+    let (source, target) = (&from.levels[level], &to.levels[level]);
+    let mut p0 = Vec::with_capacity(m);
+    let mut p1 = Vec::with_capacity(m);
+    for j in 0..m {
+        let u = evals[j] * source.weight_inverses[j];
+        let v = evals[j + m] * source.weight_inverses[j + m];
+        let slope = (v - u) * source.difference_inverses[j];
+        p0.push(u - source.points[j] * slope);
+        p1.push(slope);
+    }
+
+    let p0 = extend(from, to, &p0, level + 1);
+    let p1 = extend(from, to, &p1, level + 1);
+    (0..n)
+        .map(|j| target.weights[j] * (p0[j % m] + target.points[j] * p1[j % m]))
+        .collect()
+}
+
+/// Returns the coefficients of the vanishing polynomial of the points, a power of two of them,
+/// without its leading 1.
+fn vanishing_polynomial(points: &[Fr]) -> Vec<Fr> {
+    if points.len() == 1 {
+        return vec![-points[0]];
+    }
+
+    // (x^k + f) * (x^k + g) = x^2k + x^k * (f + g) + f * g.
+    let (left, right) = points.split_at(points.len() / 2);
+    let f = vanishing_polynomial(left);
+    let g = vanishing_polynomial(right);
+    let mut product = mul(&f, &g);
+    product.push(Fr::zero());
+    for ((c, f), g) in product[f.len()..].iter_mut().zip(&f).zip(&g) {
+        *c += *f + g;
+    }
+    product
+}
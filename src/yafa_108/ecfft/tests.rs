@@ -0,0 +1,104 @@
+use crate::yafa_108::{ecfft::*, Fr};
+use ark_ff::{One, Zero};
+use ark_std::{test_rng, vec::Vec, UniformRand};
+
+fn evaluate(coeffs: &[Fr], x: Fr) -> Fr {
+    coeffs.iter().rev().fold(Fr::zero(), |acc, c| acc * x + c)
+}
+
+fn random_coeffs(n: usize) -> Vec<Fr> {
+    let mut rng = test_rng();
+    (0..n).map(|_| Fr::rand(&mut rng)).collect()
+}
+
+#[test]
+fn test_domain_sizes() {
+    assert_eq!(EcfftDomain::new(0).unwrap().size(), 1);
+    assert_eq!(EcfftDomain::new(5).unwrap().size(), 8);
+    assert_eq!(
+        EcfftDomain::new_with_blowup(16, 4).unwrap().extended_size(),
+        64
+    );
+    assert!(EcfftDomain::new_with_blowup(16, 3).is_none());
+    assert!(EcfftDomain::new_with_blowup(1 << 20, 4).is_none());
+    assert!(EcfftDomain::new(1 << 22).is_none());
+}
+
+#[test]
+fn test_extended_elements_are_distinct() {
+    let domain = EcfftDomain::new_with_blowup(64, 4).unwrap();
+    let mut elements: Vec<Fr> = (0..domain.extended_size())
+        .map(|i| domain.extended_element(i))
+        .collect();
+    assert!(elements.iter().all(|x| !x.is_zero()));
+    elements.sort();
+    elements.dedup();
+    assert_eq!(elements.len(), domain.extended_size());
+
+    for (i, x) in domain.elements().iter().enumerate() {
+        assert_eq!(*x, domain.element(i));
+        assert_eq!(*x, domain.extended_element(i * domain.blowup()));
+    }
+}
+
+#[test]
+fn test_fft() {
+    for log_size in 0..8 {
+        let domain = EcfftDomain::new(1 << log_size).unwrap();
+        let coeffs = random_coeffs(domain.size());
+        let evals = domain.fft(&coeffs);
+        for (x, eval) in domain.elements().iter().zip(&evals) {
+            assert_eq!(*eval, evaluate(&coeffs, *x));
+        }
+    }
+
+    // Fewer coefficients are padded with zeros.
+    let domain = EcfftDomain::new(16).unwrap();
+    let coeffs = random_coeffs(5);
+    for (x, eval) in domain.elements().iter().zip(domain.fft(&coeffs)) {
+        assert_eq!(eval, evaluate(&coeffs, *x));
+    }
+}
+
+#[test]
+fn test_ifft() {
+    for log_size in 0..9 {
+        let domain = EcfftDomain::new(1 << log_size).unwrap();
+        let coeffs = random_coeffs(domain.size());
+        assert_eq!(domain.ifft(&domain.fft(&coeffs)), coeffs);
+    }
+}
+
+#[test]
+fn test_low_degree_extension() {
+    let domain = EcfftDomain::new_with_blowup(32, 4).unwrap();
+    let coeffs = random_coeffs(domain.size());
+    let extended = domain.low_degree_extension(&domain.fft(&coeffs));
+    assert_eq!(extended.len(), domain.extended_size());
+    for (i, eval) in extended.iter().enumerate() {
+        assert_eq!(*eval, evaluate(&coeffs, domain.extended_element(i)));
+    }
+
+    // Extending from a larger domain with the same extended domain agrees.
+    let larger = EcfftDomain::new_with_blowup(64, 2).unwrap();
+    let evals: Vec<Fr> = (0..larger.size())
+        .map(|i| evaluate(&coeffs, larger.element(i)))
+        .collect();
+    assert_eq!(larger.low_degree_extension(&evals), extended);
+}
+
+#[test]
+fn test_vanishing_polynomial() {
+    let mut rng = test_rng();
+    for log_size in 0..7 {
+        let domain = EcfftDomain::new(1 << log_size).unwrap();
+        for x in domain.elements() {
+            assert!(domain.evaluate_vanishing_polynomial(*x).is_zero());
+        }
+
+        for tau in [Fr::zero(), Fr::one(), Fr::rand(&mut rng)] {
+            let expected: Fr = domain.elements().iter().map(|x| tau - x).product();
+            assert_eq!(domain.evaluate_vanishing_polynomial(tau), expected);
+        }
+    }
+}
@@ -1,7 +1,177 @@
-use ark_ff::fields::{Fp576, MontBackend, MontConfig};
+use ark_ff::{
+    fields::{Fp576, MontBackend, MontConfig},
+    BigInt, Field, MontFp, Zero,
+};
 
+use crate::safegcd;
+
+pub struct FqConfig;
+pub type Fq = Fp576<MontBackend<FqConfig, 9>>;
+
+impl MontConfig<9> for FqConfig {
+    const MODULUS: BigInt<9> = BigInt!(
+        "34780837939964648916783391909097838466714157209549513956741661668420080527396705590597622124144427710754148825442779617551149172671304488041519255243374122027992030525080041"
+    );
+
+    const GENERATOR: Fq = MontFp!("11");
+
+    /// GENERATOR^t, where q - 1 = 2^3 * t
+    const TWO_ADIC_ROOT_OF_UNITY: Fq = MontFp!("20352210931107105404090576606765520961773321791847852436891069667257662057768295150100850989487484739038066884340836714918897718329742678773708674671463699964752171757206793");
+
+    #[inline(always)]
+    fn add_assign(a: &mut Fq, b: &Fq) {
+        let mut c = unrolled(a);
+        FqUnrolledConfig::add_assign(&mut c, &unrolled(b));
+        a.0 = c.0;
+    }
+
+    #[inline(always)]
+    fn sub_assign(a: &mut Fq, b: &Fq) {
+        let mut c = unrolled(a);
+        FqUnrolledConfig::sub_assign(&mut c, &unrolled(b));
+        a.0 = c.0;
+    }
+
+    #[inline(always)]
+    fn double_in_place(a: &mut Fq) {
+        let mut c = unrolled(a);
+        FqUnrolledConfig::double_in_place(&mut c);
+        a.0 = c.0;
+    }
+
+    #[inline(always)]
+    fn neg_in_place(a: &mut Fq) {
+        let mut c = unrolled(a);
+        FqUnrolledConfig::neg_in_place(&mut c);
+        a.0 = c.0;
+    }
+
+    #[inline(always)]
+    fn mul_assign(a: &mut Fq, b: &Fq) {
+        let mut c = unrolled(a);
+        FqUnrolledConfig::mul_assign(&mut c, &unrolled(b));
+        a.0 = c.0;
+    }
+
+    #[inline(always)]
+    fn square_in_place(a: &mut Fq) {
+        let mut c = unrolled(a);
+        FqUnrolledConfig::square_in_place(&mut c);
+        a.0 = c.0;
+    }
+
+    fn sum_of_products<const M: usize>(a: &[Fq; M], b: &[Fq; M]) -> Fq {
+        let (a, b) = (a.map(|a| unrolled(&a)), b.map(|b| unrolled(&b)));
+        Fq::new_unchecked(FqUnrolledConfig::sum_of_products(&a, &b).0)
+    }
+
+    /// Inverts in constant time with safegcd. The inverse of a = x * R in Montgomery form is
+    /// x^(-1) * R = a^(-1) * R^2, so R^2 is passed as the scale.
+    fn inverse(a: &Fq) -> Option<Fq> {
+        if a.is_zero() {
+            return None;
+        }
+        Some(Fq::new_unchecked(safegcd::inverse(
+            &a.0,
+            &Self::R2,
+            &SAFEGCD_MODULUS,
+        )))
+    }
+}
+
+/// A twin of [`FqConfig`] with the same modulus. Overriding `inverse` means writing out the
+/// `MontConfig` of [`FqConfig`] by hand, which would lose the unrolled arithmetic that
+/// `#[derive(MontConfig)]` generates, so [`FqConfig`] forwards all but `inverse` to this one.
 #[derive(MontConfig)]
 #[modulus = "34780837939964648916783391909097838466714157209549513956741661668420080527396705590597622124144427710754148825442779617551149172671304488041519255243374122027992030525080041"]
 #[generator = "11"]
-pub struct FqConfig;
-pub type Fq = Fp576<MontBackend<FqConfig, 9>>;
+struct FqUnrolledConfig;
+type FqUnrolled = Fp576<MontBackend<FqUnrolledConfig, 9>>;
+
+/// Reinterprets the Montgomery form of `a` as an element of [`FqUnrolled`].
+#[inline(always)]
+const fn unrolled(a: &Fq) -> FqUnrolled {
+    FqUnrolled::new_unchecked(a.0)
+}
+
+impl FqConfig {
+    /// Computes a square root of `fe`, or returns `None` if `fe` is not a square.
+    ///
+    /// Since q = 9 (mod 16), a square root of a square x is x^((q + 7) / 16) times one of
+    /// [`SQRT_MULTIPLIERS`], the square roots of the fourth roots of unity. This is the method
+    /// of RFC 9380, Appendix I.3, which needs a single exponentiation.
+    pub fn sqrt(fe: &Fq) -> Option<Fq> {
+        let t = fe.pow(MODULUS_MINUS_NINE_DIV_SIXTEEN) * fe;
+        SQRT_MULTIPLIERS
+            .iter()
+            .map(|c| t * c)
+            .find(|root| root.square() == *fe)
+    }
+
+    /// Same as [`Self::sqrt`], but in constant time, up to the timing of the arithmetic in Fq.
+    pub fn sqrt_ct(fe: &Fq) -> Option<Fq> {
+        let t = fe.pow(MODULUS_MINUS_NINE_DIV_SIXTEEN) * fe;
+        let (is_square, root) = Self::find_sqrt_ct(&t, fe);
+        (is_square == 1).then_some(root)
+    }
+
+    /// Returns 1 and the first of the `t * SQRT_MULTIPLIERS[i]` that squares to `fe`, or 0 and
+    /// zero if there is none, in constant time.
+    pub(crate) fn find_sqrt_ct(t: &Fq, fe: &Fq) -> (u64, Fq) {
+        let (mut found, mut res) = (0, Fq::ZERO);
+        for c in SQRT_MULTIPLIERS.iter() {
+            let root = *t * c;
+            let is_root = Self::ct_eq(&root.square(), fe) & !found;
+            res = Self::ct_select(&res, &root, is_root);
+            found |= is_root;
+        }
+        (found, res)
+    }
+
+    /// Returns 1 if `a == b` and 0 otherwise, without branching on their limbs.
+    pub(crate) fn ct_eq(a: &Fq, b: &Fq) -> u64 {
+        let diff = (a.0)
+            .0
+            .iter()
+            .zip((b.0).0.iter())
+            .fold(0, |acc, (a, b)| acc | (a ^ b));
+        ((diff | diff.wrapping_neg()) >> 63) ^ 1
+    }
+
+    /// Returns `b` if `choice` is 1 and `a` if it is 0, without branching on `choice`.
+    pub(crate) fn ct_select(a: &Fq, b: &Fq, choice: u64) -> Fq {
+        let mask = choice.wrapping_neg();
+        let mut res = *a;
+        for (res, b) in (res.0).0.iter_mut().zip((b.0).0.iter()) {
+            *res ^= mask & (*res ^ b);
+        }
+        res
+    }
+}
+
+/// MODULUS_MINUS_NINE_DIV_SIXTEEN = (q - 9) / 16 =
+/// 2173802371247790557298961994318614904169634825596844622296353854276255032962294099412351382759026731922134301590173726096946823291956530502594953452710882626749501907817502
+pub(crate) const MODULUS_MINUS_NINE_DIV_SIXTEEN: [u64; 9] = [
+    0xebb6b9a882a6541e,
+    0x3e5b6bc1f7c680fa,
+    0x05f33c52513be22c,
+    0xed754dec645e2ed1,
+    0x59e9490948a1cbe1,
+    0xd21b06244d9534e9,
+    0x76a4bec14953bf2b,
+    0x0172c2b21c3c833b,
+    0x0240000040af53a7,
+];
+
+/// SQRT_MULTIPLIERS = [1, sqrt(-1), sqrt(sqrt(-1)), sqrt(-sqrt(-1))], where
+/// sqrt(-1) =
+/// 5754954537812023967680934808953706302565024119056231138396924251233533902029546096100350601757152402975174732615361953833190788128461933695786743066773180633910647361975918
+pub(crate) const SQRT_MULTIPLIERS: [Fq; 4] = [
+    Fq::ONE,
+    MontFp!("5754954537812023967680934808953706302565024119056231138396924251233533902029546096100350601757152402975174732615361953833190788128461933695786743066773180633910647361975918"),
+    MontFp!("19461413274345263270916200926465761857514831495544881856730119262515706840836566579074848647895754835952605246057080635319058812301180207407754270633663364016342486992385286"),
+    MontFp!("20352210931107105404090576606765520961773321791847852436891069667257662057768295150100850989487484739038066884340836714918897718329742678773708674671463699964752171757206793"),
+];
+
+/// The modulus q in the representation of [`safegcd`]
+const SAFEGCD_MODULUS: safegcd::Modulus = safegcd::Modulus::new(&FqConfig::MODULUS);
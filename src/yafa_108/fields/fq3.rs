@@ -3,7 +3,10 @@ use ark_ff::{
     Field, MontFp,
 };
 
-use crate::yafa_108::Fq;
+use crate::{
+    lazy::FqWide,
+    yafa_108::{Fq, FqConfig, MODULUS_MINUS_NINE_DIV_SIXTEEN, SQRT_MULTIPLIERS},
+};
 
 pub type Fq3 = Fp3<Fq3Config>;
 
@@ -86,3 +89,123 @@ impl Fp3Config for Fq3Config {
         Self::FROBENIUS_COEFF_FP3_C1[1],
     ];
 }
+
+impl Fq3Config {
+    /// Multiplies `fe` by the sparse element `c0 + c1 * u`, using 5 multiplications in Fq
+    /// instead of the 6 of a generic multiplication.
+    pub fn mul_by_01(fe: &mut Fq3, c0: &Fq, c1: &Fq) {
+        let a_a = fe.c0 * c0;
+        let b_b = fe.c1 * c1;
+
+        let mut t1 = fe.c2 * c1;
+        Self::mul_fp_by_nonresidue_in_place(&mut t1);
+        t1 += &a_a;
+        let t2 = (fe.c0 + &fe.c1) * &(*c0 + c1) - &a_a - &b_b;
+        let t3 = fe.c2 * c0 + &b_b;
+
+        fe.c0 = t1;
+        fe.c1 = t2;
+        fe.c2 = t3;
+    }
+
+    /// Multiplies `fe` by the sparse element `c1 * u`, using 3 multiplications in Fq.
+    pub fn mul_by_1(fe: &mut Fq3, c1: &Fq) {
+        let mut t1 = fe.c2 * c1;
+        Self::mul_fp_by_nonresidue_in_place(&mut t1);
+        let t2 = fe.c0 * c1;
+        let t3 = fe.c1 * c1;
+
+        fe.c0 = t1;
+        fe.c1 = t2;
+        fe.c2 = t3;
+    }
+
+    /// Returns the product of `a` and `b` with unreduced coefficients, from 6 products in Fq
+    /// with Karatsuba multiplication.
+    #[inline]
+    pub(crate) fn mul_wide(a: &Fq3, b: &Fq3) -> [FqWide<FqConfig>; 3] {
+        let v0 = FqWide::mul(&a.c0, &b.c0);
+        let v1 = FqWide::mul(&a.c1, &b.c1);
+        let v2 = FqWide::mul(&a.c2, &b.c2);
+
+        // NONRESIDUE = 11
+        let t0 = FqWide::mul(&(a.c1 + &a.c2), &(b.c1 + &b.c2)) - v1 - v2;
+        let t1 = FqWide::mul(&(a.c0 + &a.c1), &(b.c0 + &b.c1)) - v0 - v1;
+        let t2 = FqWide::mul(&(a.c0 + &a.c2), &(b.c0 + &b.c2)) - v0 - v2;
+        [v0 + t0.mul_by_u64(11), t1 + v2.mul_by_u64(11), t2 + v1]
+    }
+
+    /// Returns the product of `a` and the sparse element `c0 + c1 * u` with unreduced
+    /// coefficients, from 5 products in Fq.
+    #[inline]
+    pub(crate) fn mul_by_01_wide(a: &Fq3, c0: &Fq, c1: &Fq) -> [FqWide<FqConfig>; 3] {
+        let a_a = FqWide::mul(&a.c0, c0);
+        let b_b = FqWide::mul(&a.c1, c1);
+
+        let t0 = a_a + FqWide::mul(&a.c2, c1).mul_by_u64(11);
+        let t1 = FqWide::mul(&(a.c0 + &a.c1), &(*c0 + c1)) - a_a - b_b;
+        let t2 = FqWide::mul(&a.c2, c0) + b_b;
+        [t0, t1, t2]
+    }
+
+    /// Returns the product of `a` and the sparse element `c1 * u` with unreduced coefficients,
+    /// from 3 products in Fq.
+    #[inline]
+    pub(crate) fn mul_by_1_wide(a: &Fq3, c1: &Fq) -> [FqWide<FqConfig>; 3] {
+        [
+            FqWide::mul(&a.c2, c1).mul_by_u64(11),
+            FqWide::mul(&a.c0, c1),
+            FqWide::mul(&a.c1, c1),
+        ]
+    }
+
+    /// Computes a square root of `fe`, or returns `None` if `fe` is not a square.
+    ///
+    /// Since q^3 = 9 (mod 16), this is the method of [`FqConfig::sqrt`], with the exponent
+    /// (q^3 + 7) / 16 = (q + 7) / 16 + q * (q - 1) / 8 * (q + 1) / 2. Evaluating it with a
+    /// Frobenius map takes two exponentiations by (q - 9) / 16, instead of one by a three times
+    /// longer exponent as in Tonelli-Shanks.
+    pub fn sqrt(fe: &Fq3) -> Option<Fq3> {
+        let t = Self::pow_sqrt_exponent(fe);
+        SQRT_MULTIPLIERS
+            .iter()
+            .map(|c| Fq3::new(t.c0 * c, t.c1 * c, t.c2 * c))
+            .find(|root| root.square() == *fe)
+    }
+
+    /// Same as [`Self::sqrt`], but in constant time, up to the timing of the arithmetic in Fq.
+    pub fn sqrt_ct(fe: &Fq3) -> Option<Fq3> {
+        let t = Self::pow_sqrt_exponent(fe);
+        let (mut found, mut res) = (0, Fq3::ZERO);
+        for c in SQRT_MULTIPLIERS.iter() {
+            let root = Fq3::new(t.c0 * c, t.c1 * c, t.c2 * c);
+            let square = root.square();
+            let is_root = FqConfig::ct_eq(&square.c0, &fe.c0)
+                & FqConfig::ct_eq(&square.c1, &fe.c1)
+                & FqConfig::ct_eq(&square.c2, &fe.c2)
+                & !found;
+            res = Fq3::new(
+                FqConfig::ct_select(&res.c0, &root.c0, is_root),
+                FqConfig::ct_select(&res.c1, &root.c1, is_root),
+                FqConfig::ct_select(&res.c2, &root.c2, is_root),
+            );
+            found |= is_root;
+        }
+        (found == 1).then_some(res)
+    }
+
+    /// Returns fe^((q^3 + 7) / 16).
+    fn pow_sqrt_exponent(fe: &Fq3) -> Fq3 {
+        // With c = (q + 7) / 16, it holds that (q - 1) / 8 = 2 * c - 1 and
+        // (q + 1) / 2 = 8 * (c - 1) + 5.
+        let fe_c_minus_1 = fe.pow(MODULUS_MINUS_NINE_DIV_SIXTEEN);
+        let y = fe_c_minus_1.square() * fe;
+        let mut y_q_plus_1_div_2 = y.pow(MODULUS_MINUS_NINE_DIV_SIXTEEN);
+        for _ in 0..3 {
+            y_q_plus_1_div_2.square_in_place();
+        }
+        y_q_plus_1_div_2 *= &(y.square().square() * &y);
+        y_q_plus_1_div_2.frobenius_map(1);
+        fe_c_minus_1 * fe * &y_q_plus_1_div_2
+    }
+}
@@ -1,7 +1,13 @@
-use crate::yafa_108::{Fq, Fq3, Fq3Config};
+use crate::{
+    lazy::{self, add, sub, FqWide},
+    yafa_108::{Fq, Fq3, Fq3Config, FqConfig},
+};
 use ark_ff::{
-    fields::fp6_2over3::{Fp6, Fp6Config},
-    Field, MontFp,
+    fields::{
+        fp3::Fp3Config,
+        fp6_2over3::{Fp6, Fp6Config},
+    },
+    Field, MontFp, Zero,
 };
 
 pub type Fq6 = Fp6<Fq6Config>;
@@ -25,3 +31,189 @@ impl Fp6Config for Fq6Config {
         MontFp!("7891820029458153760463333779529065900060223375068625705890303802702606422769111189933903712579664581217514620699178559520642750983460643979247817048104789503567679423896213"),
     ];
 }
+
+impl Fq6Config {
+    /// Multiplies `fe` by the sparse element `c0 + c1 * u + c4 * u * Y`, where `Y^2 = u`, using
+    /// 13 products in Fq instead of the 18 of a generic multiplication, and 6 reductions with
+    /// lazy reduction. This is the shape of the line functions of the Miller loop, after
+    /// untwisting.
+    pub fn mul_by_014(fe: &mut Fq6, c0: &Fq, c1: &Fq, c4: &Fq) {
+        let a_a = Fq3Config::mul_by_01_wide(&fe.c0, c0, c1);
+        let b_b = Fq3Config::mul_by_1_wide(&fe.c1, c4);
+        let t1 = Fq3Config::mul_by_01_wide(&(fe.c0 + &fe.c1), c0, &(*c1 + c4));
+
+        fe.c0 = reduce(&add(a_a, mul_fp3_wide_by_nonresidue(b_b)));
+        fe.c1 = reduce(&sub(sub(t1, a_a), b_b));
+    }
+
+    /// Multiplies `fe` by `other` with lazy reduction, using 18 products in Fq and 6
+    /// reductions instead of 18 multiplications in Fq.
+    pub fn lazy_mul_assign(fe: &mut Fq6, other: &Fq6) {
+        let v0 = Fq3Config::mul_wide(&fe.c0, &other.c0);
+        let v1 = Fq3Config::mul_wide(&fe.c1, &other.c1);
+        let v2 = Fq3Config::mul_wide(&(fe.c0 + &fe.c1), &(other.c0 + &other.c1));
+
+        fe.c0 = reduce(&add(v0, mul_fp3_wide_by_nonresidue(v1)));
+        fe.c1 = reduce(&sub(sub(v2, v0), v1));
+    }
+
+    /// Squares `fe` in place with lazy reduction, using the complex method with 12 products
+    /// in Fq and 6 reductions instead of 12 multiplications in Fq.
+    pub fn lazy_square_in_place(fe: &mut Fq6) -> &mut Fq6 {
+        // c0 = (a0 + a1) * (a0 + u * a1) - (1 + u) * a0 * a1, c1 = 2 * a0 * a1
+        let v0 = Fq3Config::mul_wide(&fe.c0, &fe.c1);
+        let mut a1_u = fe.c1;
+        Self::mul_fp3_by_nonresidue_in_place(&mut a1_u);
+        let v1 = Fq3Config::mul_wide(&(fe.c0 + &fe.c1), &(fe.c0 + &a1_u));
+
+        fe.c0 = reduce(&sub(sub(v1, v0), mul_fp3_wide_by_nonresidue(v0)));
+        fe.c1 = reduce(&add(v0, v0));
+        fe
+    }
+
+    /// Squares `fe`, which must be in the cyclotomic subgroup of order q^2 - q + 1, in place.
+    ///
+    /// Writing Fq6 as Fq2[Y]/(Y^3 - w), where Fq2 = Fq[w]/(w^2 - 11), this uses the method
+    /// of Granger and Scott, "Faster Squaring in the Cyclotomic Subgroup of Sixth Degree
+    /// Extensions", https://eprint.iacr.org/2009/565.pdf, with 6 multiplications in Fq instead
+    /// of 12.
+    pub fn cyclotomic_square_in_place(fe: &mut Fq6) -> &mut Fq6 {
+        let fp_nr = |mut fe: Fq| *Fq3Config::mul_fp_by_nonresidue_in_place(&mut fe);
+
+        let r0 = &fe.c0.c0;
+        let r4 = &fe.c0.c1;
+        let r3 = &fe.c0.c2;
+        let r2 = &fe.c1.c0;
+        let r1 = &fe.c1.c1;
+        let r5 = &fe.c1.c2;
+
+        // t0 + t1*w = (r0 + r1*w)^2
+        let mut tmp = *r0 * r1;
+        let t0 = (*r0 + r1) * &(fp_nr(*r1) + r0) - &tmp - &fp_nr(tmp);
+        let t1 = tmp.double();
+
+        // t2 + t3*w = (r2 + r3*w)^2
+        tmp = *r2 * r3;
+        let t2 = (*r2 + r3) * &(fp_nr(*r3) + r2) - &tmp - &fp_nr(tmp);
+        let t3 = tmp.double();
+
+        // t4 + t5*w = (r4 + r5*w)^2
+        tmp = *r4 * r5;
+        let t4 = (*r4 + r5) * &(fp_nr(*r5) + r4) - &tmp - &fp_nr(tmp);
+        let t5 = tmp.double();
+
+        let z0 = &mut fe.c0.c0;
+        // z0 = 3 * t0 - 2 * z0
+        *z0 = t0 - &*z0;
+        z0.double_in_place();
+        *z0 += &t0;
+
+        let z1 = &mut fe.c1.c1;
+        // z1 = 3 * t1 + 2 * z1
+        *z1 = t1 + &*z1;
+        z1.double_in_place();
+        *z1 += &t1;
+
+        let z2 = &mut fe.c1.c0;
+        // z2 = 3 * (11 * t5) + 2 * z2
+        tmp = fp_nr(t5);
+        *z2 += tmp;
+        z2.double_in_place();
+        *z2 += &tmp;
+
+        let z3 = &mut fe.c0.c2;
+        // z3 = 3 * t4 - 2 * z3
+        *z3 = t4 - &*z3;
+        z3.double_in_place();
+        *z3 += &t4;
+
+        let z4 = &mut fe.c0.c1;
+        // z4 = 3 * t2 - 2 * z4
+        *z4 = t2 - &*z4;
+        z4.double_in_place();
+        *z4 += &t2;
+
+        let z5 = &mut fe.c1.c2;
+        // z5 = 3 * t3 + 2 * z5
+        *z5 += t3;
+        z5.double_in_place();
+        *z5 += &t3;
+
+        fe
+    }
+
+    /// Compresses `fe`, which must be in the cyclotomic subgroup of order q^2 - q + 1, to
+    /// (g2, g3, g4, g5), from which it is recovered by [`Self::cyclotomic_decompress`]. Here
+    /// fe = g0 + g2 * Y + g4 * Y^2 + g1 * Y^3 + g3 * Y^4 + g5 * Y^5, where Y^2 = u.
+    pub fn cyclotomic_compress(fe: &Fq6) -> [Fq; 4] {
+        [fe.c1.c0, fe.c0.c2, fe.c0.c1, fe.c1.c2]
+    }
+
+    /// Squares a compressed element in place, with 4 multiplications in Fq, using the method of
+    /// Karabina, "Squaring in Cyclotomic Subgroups", https://eprint.iacr.org/2010/542.pdf.
+    ///
+    /// This is faster than [`Self::cyclotomic_square_in_place`], but decompression needs an
+    /// inversion, so it only pays off for long runs of squarings.
+    pub fn compressed_square_in_place(g: &mut [Fq; 4]) -> &mut [Fq; 4] {
+        let fp_nr = |mut fe: Fq| *Fq3Config::mul_fp_by_nonresidue_in_place(&mut fe);
+        let [g2, g3, g4, g5] = *g;
+
+        // b45 = g4 * g5, a45 = (g4 + g5) * (g4 + 11 * g5)
+        let b45 = g4 * &g5;
+        let a45 = (g4 + &g5) * &(g4 + &fp_nr(g5));
+        // b23 = g2 * g3, a23 = (g2 + g3) * (g2 + 11 * g3)
+        let b23 = g2 * &g3;
+        let a23 = (g2 + &g3) * &(g2 + &fp_nr(g3));
+
+        // h2 = 2 * (g2 + 3 * 11 * b45)
+        let tmp = fp_nr(b45);
+        g[0] = (g2 + &tmp.double() + &tmp).double();
+        // h3 = 3 * (a45 - 12 * b45) - 2 * g3
+        let tmp = a45 - &(fp_nr(b45) + &b45);
+        g[1] = tmp.double() + &tmp - &g3.double();
+        // h4 = 3 * (a23 - 12 * b23) - 2 * g4
+        let tmp = a23 - &(fp_nr(b23) + &b23);
+        g[2] = tmp.double() + &tmp - &g4.double();
+        // h5 = 2 * (g5 + 3 * b23)
+        g[3] = (g5 + &b23.double() + &b23).double();
+
+        g
+    }
+
+    /// Recovers an element of the cyclotomic subgroup from its compressed form.
+    pub fn cyclotomic_decompress(g: &[Fq; 4]) -> Fq6 {
+        let fp_nr = |mut fe: Fq| *Fq3Config::mul_fp_by_nonresidue_in_place(&mut fe);
+        let [g2, g3, g4, g5] = *g;
+
+        let g1 = if !g2.is_zero() {
+            // g1 = (11 * g5^2 + 3 * g4^2 - 2 * g3) / (4 * g2)
+            let g4_square = g4.square();
+            let num = fp_nr(g5.square()) + &g4_square.double() + &g4_square - &g3.double();
+            num * &g2.double().double().inverse().unwrap()
+        } else {
+            // g1 = 2 * g4 * g5 / g3, or 0 if g3 = 0 as well
+            g3.inverse()
+                .map(|g3_inv| (g4 * &g5).double() * &g3_inv)
+                .unwrap_or_else(Fq::zero)
+        };
+
+        // g0 = 11 * (2 * g1^2 + g2 * g5 - 3 * g3 * g4) + 1
+        let g3_g4 = g3 * &g4;
+        let g0 = fp_nr(g1.square().double() + &(g2 * &g5) - &(g3_g4.double() + &g3_g4)) + &Fq::ONE;
+
+        Fq6::new(Fq3::new(g0, g4, g3), Fq3::new(g2, g1, g5))
+    }
+}
+
+/// Multiplies an unreduced element of Fq3 by NONRESIDUE = u, where u^3 = 11.
+#[inline]
+fn mul_fp3_wide_by_nonresidue(fe: [FqWide<FqConfig>; 3]) -> [FqWide<FqConfig>; 3] {
+    [fe[2].mul_by_u64(11), fe[0], fe[1]]
+}
+
+/// Reduces the coefficients of an unreduced element of Fq3.
+#[inline]
+fn reduce(fe: &[FqWide<FqConfig>; 3]) -> Fq3 {
+    let [c0, c1, c2] = lazy::reduce(fe);
+    Fq3::new(c0, c1, c2)
+}
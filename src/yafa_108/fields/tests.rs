@@ -1,7 +1,174 @@
 use crate::yafa_108::*;
 use ark_algebra_test_templates::*;
+use ark_ff::{BigInt, BigInteger, Field, One, PrimeField};
+use ark_std::{test_rng, vec, UniformRand};
 
-test_field!(fr; Fr; mont_prime_field);
+test_field!(fr; Fr; prime_field);
 test_field!(fq; Fq; mont_prime_field);
 test_field!(fq3; Fq3);
 test_field!(fq6; Fq6);
+
+#[test]
+fn test_fq3_sparse_mul() {
+    let mut rng = test_rng();
+
+    for _ in 0..20 {
+        let a = Fq3::rand(&mut rng);
+        let c0 = Fq::rand(&mut rng);
+        let c1 = Fq::rand(&mut rng);
+
+        let mut b = a;
+        Fq3Config::mul_by_01(&mut b, &c0, &c1);
+        assert_eq!(b, a * Fq3::new(c0, c1, Fq::ZERO));
+
+        let mut b = a;
+        Fq3Config::mul_by_1(&mut b, &c1);
+        assert_eq!(b, a * Fq3::new(Fq::ZERO, c1, Fq::ZERO));
+    }
+}
+
+#[test]
+fn test_fq6_mul_by_014() {
+    let mut rng = test_rng();
+
+    for _ in 0..20 {
+        let a = Fq6::rand(&mut rng);
+        let c0 = Fq::rand(&mut rng);
+        let c1 = Fq::rand(&mut rng);
+        let c4 = Fq::rand(&mut rng);
+
+        let mut b = a;
+        Fq6Config::mul_by_014(&mut b, &c0, &c1, &c4);
+        let sparse = Fq6::new(Fq3::new(c0, c1, Fq::ZERO), Fq3::new(Fq::ZERO, c4, Fq::ZERO));
+        assert_eq!(b, a * sparse);
+    }
+}
+
+#[test]
+fn test_fq6_lazy_mul_and_square() {
+    let mut rng = test_rng();
+
+    // All coefficients -1 gives the largest unreduced sums.
+    let minus_one = -Fq6::one();
+    let all_minus_one = Fq6::new(
+        Fq3::new(-Fq::ONE, -Fq::ONE, -Fq::ONE),
+        Fq3::new(-Fq::ONE, -Fq::ONE, -Fq::ONE),
+    );
+    let mut elements = vec![Fq6::ZERO, Fq6::one(), minus_one, all_minus_one];
+    elements.extend((0..20).map(|_| Fq6::rand(&mut rng)));
+
+    for a in &elements {
+        let mut b = *a;
+        Fq6Config::lazy_square_in_place(&mut b);
+        assert_eq!(b, a.square());
+
+        for c in &elements {
+            let mut b = *a;
+            Fq6Config::lazy_mul_assign(&mut b, c);
+            assert_eq!(b, *a * c);
+        }
+    }
+}
+
+// Maps `f` to the cyclotomic subgroup by raising it to the power (q^3 - 1) * (q + 1).
+fn cyclotomic_element(f: Fq6) -> Fq6 {
+    let mut f1 = f;
+    f1.frobenius_map(3);
+    f1 *= &f.inverse().unwrap();
+    let mut f2 = f1;
+    f2.frobenius_map(1);
+    f1 * &f2
+}
+
+#[test]
+fn test_fq6_cyclotomic_square() {
+    let mut rng = test_rng();
+
+    for _ in 0..20 {
+        let a = cyclotomic_element(Fq6::rand(&mut rng));
+
+        let mut b = a;
+        Fq6Config::cyclotomic_square_in_place(&mut b);
+        assert_eq!(b, a.square());
+    }
+}
+
+#[test]
+fn test_fq6_compressed_square() {
+    let mut rng = test_rng();
+
+    for _ in 0..20 {
+        let mut a = cyclotomic_element(Fq6::rand(&mut rng));
+        let mut g = Fq6Config::cyclotomic_compress(&a);
+        assert_eq!(Fq6Config::cyclotomic_decompress(&g), a);
+
+        for _ in 0..10 {
+            Fq6Config::compressed_square_in_place(&mut g);
+            a.square_in_place();
+        }
+        assert_eq!(Fq6Config::cyclotomic_decompress(&g), a);
+    }
+
+    let g = Fq6Config::cyclotomic_compress(&Fq6::one());
+    assert_eq!(Fq6Config::cyclotomic_decompress(&g), Fq6::one());
+}
+
+#[test]
+fn test_fq_sqrt() {
+    let mut rng = test_rng();
+
+    for _ in 0..20 {
+        let a = Fq::rand(&mut rng);
+        let root = FqConfig::sqrt(&a);
+        assert_eq!(root.is_some(), a.legendre().is_qr());
+        assert_eq!(FqConfig::sqrt_ct(&a), root);
+        if let Some(root) = root {
+            assert_eq!(root.square(), a);
+        }
+
+        let square = a.square();
+        assert_eq!(FqConfig::sqrt(&square).unwrap().square(), square);
+    }
+
+    assert_eq!(FqConfig::sqrt(&Fq::ZERO), Some(Fq::ZERO));
+    assert_eq!(FqConfig::sqrt_ct(&Fq::ZERO), Some(Fq::ZERO));
+}
+
+#[test]
+fn test_fq_inverse() {
+    let mut q_minus_two = Fq::MODULUS;
+    q_minus_two.sub_with_borrow(&BigInt::from(2u64));
+
+    let mut rng = test_rng();
+
+    let mut elements = vec![Fq::one(), -Fq::one(), Fq::from(2u64), -Fq::from(2u64)];
+    elements.extend((0..50).map(|_| Fq::rand(&mut rng)));
+    for a in elements {
+        let a_inv = a.inverse().unwrap();
+        assert_eq!(a_inv, a.pow(q_minus_two));
+        assert!((a * a_inv).is_one());
+    }
+
+    assert_eq!(Fq::ZERO.inverse(), None);
+}
+
+#[test]
+fn test_fq3_sqrt() {
+    let mut rng = test_rng();
+
+    for _ in 0..20 {
+        let a = Fq3::rand(&mut rng);
+        let root = Fq3Config::sqrt(&a);
+        assert_eq!(root.is_some(), a.legendre().is_qr());
+        assert_eq!(Fq3Config::sqrt_ct(&a), root);
+        if let Some(root) = root {
+            assert_eq!(root.square(), a);
+        }
+
+        let square = a.square();
+        assert_eq!(Fq3Config::sqrt(&square).unwrap().square(), square);
+    }
+
+    assert_eq!(Fq3Config::sqrt(&Fq3::ZERO), Some(Fq3::ZERO));
+    assert_eq!(Fq3Config::sqrt_ct(&Fq3::ZERO), Some(Fq3::ZERO));
+}
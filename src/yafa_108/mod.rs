@@ -6,7 +6,9 @@
 //! equals the base field of the curve25519 curve.
 
 mod curves;
+pub mod ecfft;
 mod fields;
+pub mod poly;
 
 pub use curves::*;
 pub use fields::*;
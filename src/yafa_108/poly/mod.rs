@@ -0,0 +1,409 @@
+//! Fast polynomial arithmetic over the scalar field of Yafa-108, for polynomials given as
+//! [`DensePolynomial`]s.
+//!
+//! The scalar field 2^255 - 19 has a two-adicity of 2, so the FFT-based multiplication of
+//! `ark-poly` does not apply. Instead, products use Karatsuba's method and Toom-Cook 3-way
+//! multiplication, division uses Newton iteration on the reversed divisor, and evaluation and
+//! interpolation on arbitrary point sets use subproduct trees.
+
+use ark_ff::{batch_inversion, BigInt, Field, One, Zero};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+use ark_std::{boxed::Box, vec, vec::Vec};
+
+use crate::yafa_108::{Fr, FrConfig};
+
+#[cfg(test)]
+mod tests;
+
+/// Below this length, polynomials are multiplied with the schoolbook method.
+const KARATSUBA_THRESHOLD: usize = 32;
+
+/// From this length on, polynomials are multiplied with Toom-Cook 3-way multiplication.
+const TOOM_THRESHOLD: usize = 256;
+
+/// Below this length of the quotient or the divisor, polynomials are divided with the
+/// schoolbook method.
+const NEWTON_THRESHOLD: usize = 64;
+
+/// TWO_INV = 2^(-1) mod p =
+/// 28948022309329048855892746252171976963317496166410141009864396001978282409975
+const TWO_INV: Fr = FrConfig::const_from_bigint(BigInt!(
+    "28948022309329048855892746252171976963317496166410141009864396001978282409975"
+));
+
+/// THREE_INV = 3^(-1) mod p =
+/// 38597363079105398474523661669562635951089994888546854679819194669304376546633
+const THREE_INV: Fr = FrConfig::const_from_bigint(BigInt!(
+    "38597363079105398474523661669562635951089994888546854679819194669304376546633"
+));
+
+/// Returns the product of two polynomials.
+pub fn multiply(a: &DensePolynomial<Fr>, b: &DensePolynomial<Fr>) -> DensePolynomial<Fr> {
+    DensePolynomial::from_coefficients_vec(mul(&a.coeffs, &b.coeffs))
+}
+
+/// Returns the quotient and the remainder of the division of `a` by `b`, or `None` if `b` is
+/// zero.
+pub fn divide_with_q_and_r(
+    a: &DensePolynomial<Fr>,
+    b: &DensePolynomial<Fr>,
+) -> Option<(DensePolynomial<Fr>, DensePolynomial<Fr>)> {
+    if b.is_zero() {
+        return None;
+    }
+    let (q, r) = div_rem(&a.coeffs, &b.coeffs);
+    Some((
+        DensePolynomial::from_coefficients_vec(q),
+        DensePolynomial::from_coefficients_vec(r),
+    ))
+}
+
+/// A subproduct tree over a list of points, for evaluating polynomials on the points and
+/// interpolating from values at the points in O(M(n) log n) time, where M(n) is the time of a
+/// product of polynomials of degree n.
+///
+/// The root of the tree is the vanishing polynomial of the points, and each inner node is the
+/// product of its two children, which split the points in halves.
+#[derive(Clone, Debug)]
+pub struct SubproductTree {
+    points: Vec<Fr>,
+    root: Node,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    poly: Vec<Fr>,
+    children: Option<Box<(Node, Node)>>,
+}
+
+impl Node {
+    fn new(points: &[Fr]) -> Self {
+        if points.len() <= 1 {
+            let poly = match points.first() {
+                Some(x) => vec![-*x, Fr::one()],
+                None => vec![Fr::one()],
+            };
+            return Self {
+                poly,
+                children: None,
+            };
+        }
+
+        let (left, right) = points.split_at(points.len() / 2);
+        let (left, right) = (Node::new(left), Node::new(right));
+        Self {
+            poly: mul(&left.poly, &right.poly),
+            children: Some(Box::new((left, right))),
+        }
+    }
+
+    /// Appends the values at the points of the node of a polynomial, which is reduced modulo
+    /// the polynomial of the parent node.
+    fn evaluate(&self, coeffs: &[Fr], evals: &mut Vec<Fr>) {
+        let (_, r) = div_rem(coeffs, &self.poly);
+        match &self.children {
+            None => evals.push(r.first().copied().unwrap_or_else(Fr::zero)),
+            Some(children) => {
+                children.0.evaluate(&r, evals);
+                children.1.evaluate(&r, evals);
+            }
+        }
+    }
+
+    /// Returns the sum of weights[i] times the product of x - x_j over the points j != i of
+    /// the node.
+    fn combine(&self, weights: &[Fr]) -> Vec<Fr> {
+        match &self.children {
+            None => weights.to_vec(),
+            Some(children) => {
+                let (left, right) = (&children.0, &children.1);
+                let (left_weights, right_weights) = weights.split_at(left.poly.len() - 1);
+                let mut sum = mul(&left.combine(left_weights), &right.poly);
+                let other = mul(&right.combine(right_weights), &left.poly);
+                for (s, c) in sum.iter_mut().zip(other) {
+                    *s += c;
+                }
+                sum
+            }
+        }
+    }
+}
+
+impl SubproductTree {
+    /// Builds the subproduct tree over the points.
+    pub fn new(points: &[Fr]) -> Self {
+        Self {
+            points: points.to_vec(),
+            root: Node::new(points),
+        }
+    }
+
+    /// Returns the points of the tree.
+    pub fn points(&self) -> &[Fr] {
+        &self.points
+    }
+
+    /// Returns the vanishing polynomial of the points.
+    pub fn vanishing_polynomial(&self) -> DensePolynomial<Fr> {
+        DensePolynomial::from_coefficients_slice(&self.root.poly)
+    }
+
+    /// Evaluates the polynomial at the points.
+    pub fn evaluate(&self, poly: &DensePolynomial<Fr>) -> Vec<Fr> {
+        let mut evals = Vec::with_capacity(self.points.len());
+        if !self.points.is_empty() {
+            self.root.evaluate(&poly.coeffs, &mut evals);
+        }
+        evals
+    }
+
+    /// Returns the polynomial of degree less than the number of points with the given values
+    /// at the points, or `None` if the points are not distinct.
+    pub fn interpolate(&self, evals: &[Fr]) -> Option<DensePolynomial<Fr>> {
+        assert_eq!(
+            evals.len(),
+            self.points.len(),
+            "wrong number of evaluations"
+        );
+        if self.points.is_empty() {
+            return Some(DensePolynomial::zero());
+        }
+
+        // By Lagrange interpolation, the polynomial is the sum of y_i / z'(x_i) times the
+        // product of x - x_j over j != i, where z is the vanishing polynomial.
+        let derivative: Vec<Fr> = (1..self.root.poly.len())
+            .map(|i| self.root.poly[i] * Fr::from(i as u64))
+            .collect();
+        let mut weights = Vec::with_capacity(self.points.len());
+        self.root.evaluate(&derivative, &mut weights);
+        if weights.iter().any(|w| w.is_zero()) {
+            return None;
+        }
+        batch_inversion(&mut weights);
+        for (w, y) in weights.iter_mut().zip(evals) {
+            *w *= y;
+        }
+        Some(DensePolynomial::from_coefficients_vec(
+            self.root.combine(&weights),
+        ))
+    }
+}
+
+/// Returns the coefficients of the product of two polynomials, of which there are one fewer
+/// than the sum of the numbers of coefficients of the factors.
+pub(crate) fn mul(a: &[Fr], b: &[Fr]) -> Vec<Fr> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    if short.len() <= KARATSUBA_THRESHOLD {
+        return schoolbook_mul(short, long);
+    }
+
+    // Multiply by chunks of the long factor which are as long as the short one, and pad the
+    // last chunk.
+    let n = short.len();
+    let mut product = vec![Fr::zero(); a.len() + b.len() - 1];
+    for (i, chunk) in long.chunks(n).enumerate() {
+        let mut chunk = chunk.to_vec();
+        chunk.resize(n, Fr::zero());
+        for (p, c) in product[i * n..].iter_mut().zip(balanced_mul(short, &chunk)) {
+            *p += c;
+        }
+    }
+    product
+}
+
+/// Multiplies two polynomials with n coefficients each, and returns the 2n - 1 coefficients of
+/// the product.
+fn balanced_mul(a: &[Fr], b: &[Fr]) -> Vec<Fr> {
+    debug_assert_eq!(a.len(), b.len());
+    if a.len() <= KARATSUBA_THRESHOLD {
+        schoolbook_mul(a, b)
+    } else if a.len() < TOOM_THRESHOLD {
+        karatsuba_mul(a, b)
+    } else {
+        toom3_mul(a, b)
+    }
+}
+
+fn schoolbook_mul(a: &[Fr], b: &[Fr]) -> Vec<Fr> {
+    let mut product = vec![Fr::zero(); a.len() + b.len() - 1];
+    for (i, a) in a.iter().enumerate() {
+        for (p, b) in product[i..].iter_mut().zip(b) {
+            *p += *a * b;
+        }
+    }
+    product
+}
+
+fn karatsuba_mul(a: &[Fr], b: &[Fr]) -> Vec<Fr> {
+    // With a = a0 + x^k a1 and b = b0 + x^k b1, the middle part of the product is
+    // (a0 + a1) (b0 + b1) - a0 b0 - a1 b1.
+    let n = a.len();
+    let k = n / 2;
+    let (a0, a1) = a.split_at(k);
+    let (b0, b1) = b.split_at(k);
+    let low = balanced_mul(a0, b0);
+    let high = balanced_mul(a1, b1);
+    let mut middle = balanced_mul(&add(a1, a0), &add(b1, b0));
+    sub_assign(&mut middle, &low);
+    sub_assign(&mut middle, &high);
+
+    let mut product = vec![Fr::zero(); 2 * n - 1];
+    add_assign(&mut product, &low);
+    add_assign(&mut product[2 * k..], &high);
+    add_assign(&mut product[k..], &middle);
+    product
+}
+
+fn toom3_mul(a: &[Fr], b: &[Fr]) -> Vec<Fr> {
+    // Split a = a0 + x^k a1 + x^2k a2 and likewise b, evaluate the parts at 0, 1, -1, -2 and
+    // infinity, multiply pointwise, and interpolate with Bodrato's sequence.
+    let n = a.len();
+    let k = n.div_ceil(3);
+    let split = |p: &[Fr]| {
+        let mut parts = [
+            vec![Fr::zero(); k],
+            vec![Fr::zero(); k],
+            vec![Fr::zero(); k],
+        ];
+        for (i, chunk) in p.chunks(k).enumerate() {
+            parts[i][..chunk.len()].copy_from_slice(chunk);
+        }
+        parts
+    };
+    let evaluate = |[p0, p1, p2]: [Vec<Fr>; 3]| {
+        let sum = add(&p0, &p2);
+        let at_one = add(&sum, &p1);
+        let at_minus_one = sub(&sum, &p1);
+        // p(-2) = 2 (p(-1) + p2) - p0.
+        let mut at_minus_two = add(&at_minus_one, &p2);
+        for c in at_minus_two.iter_mut() {
+            c.double_in_place();
+        }
+        sub_assign(&mut at_minus_two, &p0);
+        [p0, at_one, at_minus_one, at_minus_two, p2]
+    };
+    let [a0, a1, a_1, a_2, a_inf] = evaluate(split(a));
+    let [b0, b1, b_1, b_2, b_inf] = evaluate(split(b));
+
+    let r0 = balanced_mul(&a0, &b0);
+    let r_one = balanced_mul(&a1, &b1);
+    let r_minus_one = balanced_mul(&a_1, &b_1);
+    let r_minus_two = balanced_mul(&a_2, &b_2);
+    let r4 = balanced_mul(&a_inf, &b_inf);
+
+    let mut r3: Vec<Fr> = sub(&r_minus_two, &r_one)
+        .into_iter()
+        .map(|c| c * THREE_INV)
+        .collect();
+    let mut r1: Vec<Fr> = sub(&r_one, &r_minus_one)
+        .into_iter()
+        .map(|c| c * TWO_INV)
+        .collect();
+    let mut r2 = sub(&r_minus_one, &r0);
+    for ((c3, c2), c4) in r3.iter_mut().zip(&r2).zip(&r4) {
+        *c3 = (*c2 - *c3) * TWO_INV + c4.double();
+    }
+    add_assign(&mut r2, &r1);
+    sub_assign(&mut r2, &r4);
+    sub_assign(&mut r1, &r3);
+
+    let mut product = vec![Fr::zero(); 4 * k + 2 * k - 1];
+    for (i, r) in [r0, r1, r2, r3, r4].iter().enumerate() {
+        add_assign(&mut product[i * k..], r);
+    }
+    product.truncate(2 * n - 1);
+    product
+}
+
+/// Returns the quotient and the remainder of the division of `a` by `b`, whose leading
+/// coefficient must be nonzero.
+pub(crate) fn div_rem(a: &[Fr], b: &[Fr]) -> (Vec<Fr>, Vec<Fr>) {
+    let (n, m) = (a.len(), b.len());
+    assert!(
+        !b[m - 1].is_zero(),
+        "the leading coefficient of the divisor is zero"
+    );
+    if n < m {
+        return (Vec::new(), a.to_vec());
+    }
+
+    let quotient_len = n - m + 1;
+    let q = if quotient_len.min(m) < NEWTON_THRESHOLD {
+        schoolbook_div(a, b)
+    } else {
+        // With rev(p) = x^deg(p) p(1 / x), rev(a) = rev(q) rev(b) + x^(n - m + 1) rev(r), so
+        // rev(q) is rev(a) / rev(b) modulo x^(n - m + 1).
+        let a_rev: Vec<Fr> = a.iter().rev().take(quotient_len).copied().collect();
+        let b_rev: Vec<Fr> = b.iter().rev().take(quotient_len).copied().collect();
+        let mut q = mul(&a_rev, &inverse_mod_power(&b_rev, quotient_len));
+        q.truncate(quotient_len);
+        q.reverse();
+        q
+    };
+
+    let mut r = a[..m - 1].to_vec();
+    let lower_b = &b[..(m - 1).min(b.len())];
+    let lower_q = &q[..(m - 1).min(q.len())];
+    sub_assign(&mut r, &mul(lower_q, lower_b));
+    (q, r)
+}
+
+/// Returns the quotient of the division of `a` by `b`, with `a` at least as long as `b`.
+fn schoolbook_div(a: &[Fr], b: &[Fr]) -> Vec<Fr> {
+    let m = b.len();
+    let leading_inverse = b[m - 1].inverse().unwrap();
+    let mut r = a.to_vec();
+    let mut q = vec![Fr::zero(); a.len() - m + 1];
+    for i in (0..q.len()).rev() {
+        let c = r[i + m - 1] * leading_inverse;
+        for (r, b) in r[i..].iter_mut().zip(b) {
+            *r -= c * b;
+        }
+        q[i] = c;
+    }
+    q
+}
+
+/// Returns the inverse of the power series f modulo x^n, whose constant coefficient must be
+/// nonzero, with Newton iteration g <- g (2 - f g).
+fn inverse_mod_power(f: &[Fr], n: usize) -> Vec<Fr> {
+    let mut g = vec![f[0].inverse().unwrap()];
+    while g.len() < n {
+        // f g = 1 + x^k h modulo x^2k, and the next iterate is g - x^k g h.
+        let k = g.len();
+        let len = (2 * k).min(n);
+        let mut h = mul(&f[..len.min(f.len())], &g);
+        h.resize(len, Fr::zero());
+        let mut correction = mul(&g, &h[k..]);
+        correction.truncate(len - k);
+        g.extend(correction.into_iter().map(|c| -c));
+    }
+    g
+}
+
+fn add(a: &[Fr], b: &[Fr]) -> Vec<Fr> {
+    let mut sum = a.to_vec();
+    add_assign(&mut sum, b);
+    sum
+}
+
+fn sub(a: &[Fr], b: &[Fr]) -> Vec<Fr> {
+    let mut difference = a.to_vec();
+    sub_assign(&mut difference, b);
+    difference
+}
+
+fn add_assign(a: &mut [Fr], b: &[Fr]) {
+    for (a, b) in a.iter_mut().zip(b) {
+        *a += b;
+    }
+}
+
+fn sub_assign(a: &mut [Fr], b: &[Fr]) {
+    for (a, b) in a.iter_mut().zip(b) {
+        *a -= b;
+    }
+}
@@ -0,0 +1,113 @@
+use crate::yafa_108::{poly::*, Fr};
+use ark_ff::{One, Zero};
+use ark_poly::{
+    univariate::{DenseOrSparsePolynomial, DensePolynomial},
+    DenseUVPolynomial, Polynomial,
+};
+use ark_std::{test_rng, vec, vec::Vec, UniformRand};
+
+fn naive_mul(a: &DensePolynomial<Fr>, b: &DensePolynomial<Fr>) -> DensePolynomial<Fr> {
+    if a.is_zero() || b.is_zero() {
+        return DensePolynomial::zero();
+    }
+    let mut product = vec![Fr::zero(); a.coeffs.len() + b.coeffs.len() - 1];
+    for (i, a) in a.coeffs.iter().enumerate() {
+        for (j, b) in b.coeffs.iter().enumerate() {
+            product[i + j] += *a * b;
+        }
+    }
+    DensePolynomial::from_coefficients_vec(product)
+}
+
+#[test]
+fn test_multiply() {
+    let mut rng = test_rng();
+    let sizes = [0, 1, 2, 7, 33, 64, 100, 255, 256, 300, 800];
+    for &n in &sizes {
+        for &m in &sizes {
+            let a = DensePolynomial::<Fr>::rand(n, &mut rng);
+            let b = DensePolynomial::<Fr>::rand(m, &mut rng);
+            assert_eq!(
+                multiply(&a, &b),
+                naive_mul(&a, &b),
+                "degrees {} and {}",
+                n,
+                m
+            );
+        }
+    }
+    let a = DensePolynomial::<Fr>::rand(10, &mut rng);
+    assert!(multiply(&a, &DensePolynomial::zero()).is_zero());
+}
+
+#[test]
+fn test_divide_with_q_and_r() {
+    let mut rng = test_rng();
+    for (n, m) in [
+        (0, 0),
+        (5, 9),
+        (9, 5),
+        (100, 1),
+        (100, 70),
+        (300, 100),
+        (600, 300),
+    ] {
+        let a = DensePolynomial::<Fr>::rand(n, &mut rng);
+        let b = DensePolynomial::<Fr>::rand(m, &mut rng);
+        let (q, r) = divide_with_q_and_r(&a, &b).unwrap();
+        assert!(r.is_zero() || r.degree() < b.degree());
+        assert_eq!(&multiply(&q, &b) + &r, a);
+
+        let expected = DenseOrSparsePolynomial::from(&a)
+            .divide_with_q_and_r(&DenseOrSparsePolynomial::from(&b))
+            .unwrap();
+        assert_eq!((q, r), expected);
+    }
+
+    let a = DensePolynomial::<Fr>::rand(10, &mut rng);
+    assert!(divide_with_q_and_r(&a, &DensePolynomial::zero()).is_none());
+}
+
+#[test]
+fn test_subproduct_tree() {
+    let mut rng = test_rng();
+    for n in [1, 2, 3, 10, 100, 257] {
+        let points: Vec<Fr> = (0..n).map(|_| Fr::rand(&mut rng)).collect();
+        let tree = SubproductTree::new(&points);
+
+        let z = tree.vanishing_polynomial();
+        assert_eq!(z.degree(), n);
+        assert!(points.iter().all(|x| z.evaluate(x).is_zero()));
+
+        for degree in [0, n / 2, n - 1, 2 * n + 3] {
+            let poly = DensePolynomial::<Fr>::rand(degree, &mut rng);
+            let expected: Vec<Fr> = points.iter().map(|x| poly.evaluate(x)).collect();
+            assert_eq!(tree.evaluate(&poly), expected);
+        }
+
+        let evals: Vec<Fr> = (0..n).map(|_| Fr::rand(&mut rng)).collect();
+        let poly = tree.interpolate(&evals).unwrap();
+        assert!(poly.is_zero() || poly.degree() < n);
+        assert_eq!(tree.evaluate(&poly), evals);
+    }
+
+    let tree = SubproductTree::new(&[]);
+    assert_eq!(
+        tree.vanishing_polynomial(),
+        DensePolynomial::from_coefficients_vec(vec![Fr::one()])
+    );
+    assert!(tree
+        .evaluate(&DensePolynomial::rand(3, &mut rng))
+        .is_empty());
+    assert!(tree.interpolate(&[]).unwrap().is_zero());
+}
+
+#[test]
+fn test_interpolate_repeated_points() {
+    let mut rng = test_rng();
+    let x = Fr::rand(&mut rng);
+    let tree = SubproductTree::new(&[x, Fr::one(), x]);
+    assert!(tree
+        .interpolate(&[Fr::one(), Fr::one(), Fr::one()])
+        .is_none());
+}
@@ -1,13 +1,20 @@
 use ark_ec::{
+    hashing::map_to_curve_hasher::MapToCurveBasedHasher,
     models::CurveConfig,
     short_weierstrass::{Affine, Projective, SWCurveConfig},
     AffineRepr, CurveGroup,
 };
-use ark_ff::{Field, MontFp};
+use ark_ff::{BigInt, Field, MontFp};
 use ark_serialize::*;
 use ark_std::{ops::Neg, vec, vec::Vec, One};
+use sha2::{Sha256, Sha512};
 
-use crate::yafa_146::{Fq, Fq2, Fr, TATE_LOOP_COUNT};
+use crate::serialization::deserialize_with_sqrt;
+use crate::{
+    glv::{self, Decomposition, EndomorphismConfig},
+    hashing::{SvdwConfig, SvdwMap, XmdFieldHasher},
+    yafa_146::{Fq, Fq2, Fq2Config, Fr, FrConfig, ATE_LOOP_COUNTS},
+};
 
 pub type G2Affine = Affine<Parameters>;
 pub type G2Projective = Projective<Parameters>;
@@ -15,10 +22,18 @@ pub type G2Projective = Projective<Parameters>;
 #[derive(Clone, Default, PartialEq, Eq)]
 pub struct Parameters;
 
+/// The line coefficients of the optimal ate Miller loop at a point of G2.
+///
+/// This stores 345 line coefficients per point, against 259 for the Tate loop over r, so it does
+/// not save memory: the bit lengths of the coordinates of every short vector of the lattice of
+/// `ATE_LOOP_COUNTS` sum to about log2(r), and each loop count needs about one line per bit.
+/// The optimal ate pairing saves the Fq12 squarings instead, which the four loops share.
 #[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct G2Prepared {
     // Stores the coefficients of the line evaluations as calculated in
-    // https://eprint.iacr.org/2013/722.pdf
+    // https://eprint.iacr.org/2013/722.pdf, for the four Miller loops of
+    // ATE_LOOP_COUNTS[i] at psi^i(Q) interleaved in the order in which the
+    // Miller loop consumes them, followed by the two lines that combine them
     pub ell_coeffs: Vec<EllCoeff<Fq2>>,
     pub infinity: bool,
 }
@@ -32,6 +47,21 @@ struct G2HomProjective {
     z: Fq2,
 }
 
+impl G2HomProjective {
+    fn new(q: &G2Affine) -> Self {
+        G2HomProjective {
+            x: q.x,
+            y: q.y,
+            z: Fq2::one(),
+        }
+    }
+
+    fn into_affine(self) -> G2Affine {
+        let z_inv = self.z.inverse().unwrap();
+        G2Affine::new_unchecked(self.x * &z_inv, self.y * &z_inv)
+    }
+}
+
 impl Default for G2Prepared {
     fn default() -> Self {
         Self::from(G2Affine::generator())
@@ -48,24 +78,41 @@ impl From<G2Affine> for G2Prepared {
             },
             false => {
                 let mut ell_coeffs = vec![];
-                let mut r = G2HomProjective {
-                    x: q.x,
-                    y: q.y,
-                    z: Fq2::one(),
-                };
-
-                let neg_q = q.neg();
-                for i in TATE_LOOP_COUNT.iter().skip(1) {
-                    ell_coeffs.push(doubling_step(&mut r, &two_inv));
-
-                    let coeff = match i {
-                        1 => addition_step(&mut r, &q),
-                        -1 => addition_step(&mut r, &neg_q),
-                        0 => continue,
-                        _ => unreachable!(),
-                    };
-                    ell_coeffs.push(coeff);
+
+                // The base points psi^i(Q), and the running points of their Miller loops,
+                // which start at +/- psi^i(Q) according to the leading digit.
+                let mut qs = [q; 4];
+                for i in 1..4 {
+                    qs[i] = psi(&qs[i - 1]);
                 }
+                let mut rs = [0, 1, 2, 3].map(|i| match ATE_LOOP_COUNTS[i][0] {
+                    1 => G2HomProjective::new(&qs[i]),
+                    -1 => G2HomProjective::new(&qs[i].neg()),
+                    _ => unreachable!(),
+                });
+
+                for j in 1..ATE_LOOP_COUNTS[0].len() {
+                    for r in rs.iter_mut() {
+                        ell_coeffs.push(doubling_step(r, &two_inv));
+                    }
+
+                    for ((r, q), loop_count) in rs.iter_mut().zip(&qs).zip(ATE_LOOP_COUNTS) {
+                        let coeff = match loop_count[j] {
+                            1 => addition_step(r, q),
+                            -1 => addition_step(r, &q.neg()),
+                            0 => continue,
+                            _ => unreachable!(),
+                        };
+                        ell_coeffs.push(coeff);
+                    }
+                }
+
+                // The lines through [c3 * q^3]Q and [c2 * q^2]Q, and through their sum and
+                // [c1 * q]Q. The last line, through [c1 * q + c2 * q^2 + c3 * q^3]Q and
+                // [c0]Q, is vertical and is eliminated by the final exponentiation.
+                let [_, mut r1, mut r2, r3] = rs;
+                ell_coeffs.push(addition_step(&mut r2, &r3.into_affine()));
+                ell_coeffs.push(addition_step(&mut r1, &r2.into_affine()));
 
                 Self {
                     ell_coeffs,
@@ -100,6 +147,40 @@ impl G2Prepared {
     }
 }
 
+/// Returns the line coefficients of the Miller loop of `loop_count` at the non-zero point `q`,
+/// in the order in which the Miller loop consumes them.
+pub(crate) fn ell_coeffs(q: &G2Affine, loop_count: &[i8]) -> Vec<EllCoeff<Fq2>> {
+    let two_inv = Fq::one().double().inverse().unwrap();
+    let mut ell_coeffs = vec![];
+    let mut r = G2HomProjective::new(q);
+
+    let neg_q = q.neg();
+    for i in loop_count.iter().skip(1) {
+        ell_coeffs.push(doubling_step(&mut r, &two_inv));
+
+        let coeff = match i {
+            1 => addition_step(&mut r, q),
+            -1 => addition_step(&mut r, &neg_q),
+            0 => continue,
+            _ => unreachable!(),
+        };
+        ell_coeffs.push(coeff);
+    }
+    ell_coeffs
+}
+
+/// The untwist-Frobenius-twist endomorphism psi(x, y) = (x^q * PSI_COEFF_X, y^q * PSI_COEFF_Y),
+/// which acts on G2 as multiplication by q.
+pub fn psi(p: &G2Affine) -> G2Affine {
+    let mut x = p.x;
+    let mut y = p.y;
+    x.frobenius_map(1);
+    y.frobenius_map(1);
+    x.mul_assign_by_fp(&PSI_COEFF_X);
+    y.mul_assign_by_fp(&PSI_COEFF_Y);
+    G2Affine::new_unchecked(x, y)
+}
+
 fn doubling_step(r: &mut G2HomProjective, two_inv: &Fq) -> EllCoeff<Fq2> {
     // Formula for line function when working with
     // homogeneous projective coordinates.
@@ -174,8 +255,9 @@ impl CurveConfig for Parameters {
 
     /// COFACTOR^(-1) mod r =
     /// 52342251624758656962424635542600246432390708264947618736639330869844643473789
-    const COFACTOR_INV: Fr =
-        MontFp!("52342251624758656962424635542600246432390708264947618736639330869844643473789");
+    const COFACTOR_INV: Fr = FrConfig::const_from_bigint(BigInt!(
+        "52342251624758656962424635542600246432390708264947618736639330869844643473789"
+    ));
 }
 
 impl SWCurveConfig for Parameters {
@@ -199,8 +281,224 @@ impl SWCurveConfig for Parameters {
     // 7637611826882570078699336838344331626954875886166837539349405006589346957661356360935844425
     // 3845876818888589332253391608769436909946181275415785184259524560205574601021756234710113763
     // 2689382898715418246998285567880568710254458669018456424437988969476952804
+
+    /// Decompresses with [`Fq2Config::sqrt`].
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<G2Affine, SerializationError> {
+        deserialize_with_sqrt(reader, compress, validate, Fq2Config::sqrt)
+    }
+
+    /// Multiplies with the 4-dimensional GLS method for scalars below r. The base must be in
+    /// G2, as for other points the result is not the multiple.
+    fn mul_projective(base: &G2Projective, scalar: &[u64]) -> G2Projective {
+        glv::mul_projective(base, scalar)
+    }
+
+    /// Multiplies with the 4-dimensional GLS method for scalars below r, as
+    /// [`Self::mul_projective`].
+    fn mul_affine(base: &G2Affine, scalar: &[u64]) -> G2Projective {
+        glv::mul_projective(&base.into_group(), scalar)
+    }
+
+    /// Splits the scalars with the 4-dimensional GLS method.
+    fn msm(bases: &[G2Affine], scalars: &[Fr]) -> Result<G2Projective, usize> {
+        glv::msm(bases, scalars)
+    }
+
+    /// Multiplies by the effective cofactor, in the manner of Budroni and Pintore, "Efficient
+    /// hash maps to G2 on BLS curves", which quarters the length of the cofactor.
+    fn clear_cofactor(p: &G2Affine) -> G2Affine {
+        let p = G2Projective::from(*p);
+        let psi = <Self as EndomorphismConfig<4>>::endomorphism(&p);
+        let [phi, psi_phi] = [p, psi].map(|mut p| {
+            p.x.mul_assign_by_fp(&PHI_COEFF_X);
+            p
+        });
+        glv::linear_combination(&[p, psi, phi, psi_phi], &EFFECTIVE_COFACTOR).into_affine()
+    }
+
+    /// Checks that v_0 + v_1 * psi + v_2 * psi^2 + v_3 * psi^3 maps the point to zero for the
+    /// first basis vector v of the GLS decomposition, of which the kernel in E'(Fq2) is G2.
+    fn is_in_correct_subgroup_assuming_on_curve(p: &G2Affine) -> bool {
+        glv::is_in_correct_subgroup(p)
+    }
 }
 
+impl EndomorphismConfig<4> for Parameters {
+    /// The decomposition k = k_0 + k_1 * q + k_2 * q^2 + k_3 * q^3 mod r, with the LLL-reduced
+    /// basis
+    ///
+    /// (13983351536249462271, 8111381323569501101, 5221977675096255291, -72776397645554489),
+    /// (-13910575138603907782, 5871970212679961170, 2816627250827691321, 5294754072741809780),
+    /// (-2816627250827691321, -5294754072741809780, -11093947887776216461, 11166724285421770950),
+    /// (-5294754072741809780, -13910575138603907782, 11166724285421770950, 2816627250827691321)
+    ///
+    /// of determinant -r, for which the parts are below 0.75 times the sum of the absolute values
+    /// in their column, so below 2^65.
+    const DECOMPOSITION: Decomposition<4> = Decomposition {
+        eigenvalue: Q_MOD_R,
+        basis: [
+            [
+                FrConfig::const_from_bigint(BigInt!("13983351536249462271")),
+                FrConfig::const_from_bigint(BigInt!("8111381323569501101")),
+                FrConfig::const_from_bigint(BigInt!("5221977675096255291")),
+                FrConfig::const_from_bigint(BigInt!(
+                    "57896044618658097711785492504343953926634992332820282019728719227558919265460"
+                )),
+            ],
+            [
+                FrConfig::const_from_bigint(BigInt!(
+                    "57896044618658097711785492504343953926634992332820282019714881428817960912167"
+                )),
+                FrConfig::const_from_bigint(BigInt!("5871970212679961170")),
+                FrConfig::const_from_bigint(BigInt!("2816627250827691321")),
+                FrConfig::const_from_bigint(BigInt!("5294754072741809780")),
+            ],
+            [
+                FrConfig::const_from_bigint(BigInt!(
+                    "57896044618658097711785492504343953926634992332820282019725975376705737128628"
+                )),
+                FrConfig::const_from_bigint(BigInt!(
+                    "57896044618658097711785492504343953926634992332820282019723497249883823010169"
+                )),
+                FrConfig::const_from_bigint(BigInt!(
+                    "57896044618658097711785492504343953926634992332820282019717698056068788603488"
+                )),
+                FrConfig::const_from_bigint(BigInt!("11166724285421770950")),
+            ],
+            [
+                FrConfig::const_from_bigint(BigInt!(
+                    "57896044618658097711785492504343953926634992332820282019723497249883823010169"
+                )),
+                FrConfig::const_from_bigint(BigInt!(
+                    "57896044618658097711785492504343953926634992332820282019714881428817960912167"
+                )),
+                FrConfig::const_from_bigint(BigInt!("11166724285421770950")),
+                FrConfig::const_from_bigint(BigInt!("2816627250827691321")),
+            ],
+        ],
+        rounding: [
+            (
+                false,
+                [0xa3c15836bc9b94d4, 0x50b6b83a9ecb0031, 0xc7221fdb4bf75190],
+            ),
+            (
+                true,
+                [0x8908c0eaddca65de, 0xa1a30ee0d62ac380, 0xa1b7ee413a9ac174],
+            ),
+            (
+                false,
+                [0x63a52ba126857e96, 0x556730cf8a0aab72, 0x48e842147c7d4c8f],
+            ),
+            (
+                false,
+                [0xc0fb32334ac31a08, 0xf8425f7f8bbb0e43, 0x1419cf9b01c66454],
+            ),
+        ],
+        num_bits: 65,
+    };
+
+    /// Returns psi(P) in Jacobian coordinates, in which the conjugation commutes with the division
+    /// by powers of Z.
+    fn endomorphism(p: &G2Projective) -> G2Projective {
+        let mut res = *p;
+        res.x.frobenius_map(1);
+        res.y.frobenius_map(1);
+        res.z.frobenius_map(1);
+        res.x.mul_assign_by_fp(&PSI_COEFF_X);
+        res.y.mul_assign_by_fp(&PSI_COEFF_Y);
+        res
+    }
+
+    /// Returns psi(P).
+    fn endomorphism_affine(p: &G2Affine) -> G2Affine {
+        if p.infinity {
+            *p
+        } else {
+            psi(p)
+        }
+    }
+}
+
+impl SvdwConfig for Parameters {
+    /// Z = 1
+    const Z: Fq2 = Fq2::new(MontFp!("1"), Fq::ZERO);
+
+    fn sqrt(fe: &Fq2) -> Option<Fq2> {
+        Fq2Config::sqrt(fe)
+    }
+}
+
+/// The suite YAFA146G2_XMD:SHA-256_SVDW_RO_ of RFC 9380, hashing to G2 with
+/// expand_message_xmd with SHA-256, the SvdW map on the twist and the effective cofactor.
+pub type G2Sha256Hasher =
+    MapToCurveBasedHasher<G2Projective, XmdFieldHasher<Sha256>, SvdwMap<Parameters>>;
+
+/// The suite YAFA146G2_XMD:SHA-512_SVDW_RO_ of RFC 9380, which is [`G2Sha256Hasher`] with
+/// SHA-512.
+pub type G2Sha512Hasher =
+    MapToCurveBasedHasher<G2Projective, XmdFieldHasher<Sha512>, SvdwMap<Parameters>>;
+
+/// The suite ID of [`G2Sha256Hasher`], to be part of the DSTs of applications.
+pub const G2_SHA256_SUITE_ID: &[u8] = b"YAFA146G2_XMD:SHA-256_SVDW_RO_";
+
+/// The suite ID of [`G2Sha512Hasher`].
+pub const G2_SHA512_SUITE_ID: &[u8] = b"YAFA146G2_XMD:SHA-512_SVDW_RO_";
+
+/// The effective cofactor c_0 + c_1 * psi + c_2 * phi + c_3 * psi * phi with
+///
+/// c_0 = -6386602613746936371884966975975860383214066966405468233772289999876,
+/// c_1 = 793825954548571144471425409842462093615962521937900247281307469217,
+/// c_2 = 4984935509885852072157467315782440741937500052060591338436605710937,
+/// c_3 = 7864195837782706691141013537814659500207246647328775628776588796993,
+///
+/// a short vector of the lattice of coefficients for which it lies in the ideal of
+/// End(E') = Z[phi] of norm COFACTOR that divides pi - 1 for the Frobenius endomorphism pi over
+/// Fq2, so that it kills the points of E'(Fq2) of order dividing COFACTOR, but not G2.
+const EFFECTIVE_COFACTOR: [(bool, BigInt<4>); 4] = [
+    (
+        true,
+        BigInt!("6386602613746936371884966975975860383214066966405468233772289999876"),
+    ),
+    (
+        false,
+        BigInt!("793825954548571144471425409842462093615962521937900247281307469217"),
+    ),
+    (
+        false,
+        BigInt!("4984935509885852072157467315782440741937500052060591338436605710937"),
+    ),
+    (
+        false,
+        BigInt!("7864195837782706691141013537814659500207246647328775628776588796993"),
+    ),
+];
+
+/// PHI_COEFF_X = 22684684505229882416948285270218997440839683235327132785256469607245693680604029123404282437563474551944295516999841064542480240558845597162904726440521080541363789749413059
+///
+/// The cube root of unity such that phi(x, y) = (x * PHI_COEFF_X, y) acts on G2 as
+/// multiplication by LAMBDA.
+pub const PHI_COEFF_X: Fq = MontFp!("22684684505229882416948285270218997440839683235327132785256469607245693680604029123404282437563474551944295516999841064542480240558845597162904726440521080541363789749413059");
+
+/// Q_MOD_R = q mod r =
+/// 31273683262466106323163698086196361327227381373853663341692366900547029930933
+///
+/// The eigenvalue of psi on G2, a primitive 12th root of unity mod r.
+pub const Q_MOD_R: Fr = FrConfig::const_from_bigint(BigInt!(
+    "31273683262466106323163698086196361327227381373853663341692366900547029930933"
+));
+
+/// PSI_COEFF_X = Fq6::NONRESIDUE^((q - 1) / 3) =
+/// 22684684505229882416948285270218997440839683235327132785256469607245693680604029123404282437563474551944295516999841064542480240558845597162904726440521080541363789749413060
+pub const PSI_COEFF_X: Fq = MontFp!("22684684505229882416948285270218997440839683235327132785256469607245693680604029123404282437563474551944295516999841064542480240558845597162904726440521080541363789749413060");
+
+/// PSI_COEFF_Y = Fq6::NONRESIDUE^((q - 1) / 2) =
+/// 10907262302462287557760590083099326333305744449331424419812495591525729643523851429020508443027605463784203812151719095264580234048899993127396134080477967224420449328059879
+pub const PSI_COEFF_Y: Fq = MontFp!("10907262302462287557760590083099326333305744449331424419812495591525729643523851429020508443027605463784203812151719095264580234048899993127396134080477967224420449328059879");
+
 const G2_GENERATOR_X: Fq2 = Fq2::new(G2_GENERATOR_X_C0, G2_GENERATOR_X_C1);
 const G2_GENERATOR_Y: Fq2 = Fq2::new(G2_GENERATOR_Y_C0, G2_GENERATOR_Y_C1);
 
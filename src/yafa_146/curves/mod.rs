@@ -3,13 +3,25 @@ use ark_ff::{biginteger::BigInt, fields::Field, CyclotomicMultSubgroup, One};
 use ark_std::{cfg_chunks_mut, vec::Vec};
 use itertools::Itertools;
 
-use crate::yafa_146::{Fq, Fq12, Fq2, Fr};
+use crate::{
+    cyclotomic::cyclotomic_multi_exp,
+    yafa_146::{Fq, Fq12, Fq12Config, Fq2, Fr},
+};
 
 pub mod g1;
-pub use self::g1::{G1Affine, G1Prepared, G1Projective};
+pub use self::g1::{
+    G1Affine, G1Prepared, G1Projective, G1Sha256Hasher, G1Sha512Hasher, G1_SHA256_SUITE_ID,
+    G1_SHA512_SUITE_ID,
+};
 
 pub mod g2;
-pub use self::g2::{G2Affine, G2Prepared, G2Projective};
+pub use self::g2::{
+    G2Affine, G2Prepared, G2Projective, G2Sha256Hasher, G2Sha512Hasher, G2_SHA256_SUITE_ID,
+    G2_SHA512_SUITE_ID,
+};
+
+pub mod torus;
+pub use self::torus::TorusPairingOutput;
 
 use ark_ec::pairing::{MillerLoopOutput, PairingOutput};
 #[cfg(feature = "parallel")]
@@ -22,6 +34,29 @@ mod tests;
 pub struct Yafa;
 
 impl Yafa {
+    /// The Tate pairing with the loop count r, evaluated with the arguments swapped.
+    /// This is kept as a reference, since the optimal ate pairing computed by [`Pairing`]
+    /// equals it raised to a fixed power.
+    pub fn tate_pairing(p: &G1Affine, q: &G2Affine) -> Fq12 {
+        let p = G1Prepared::from(p);
+        let mut f = Fq12::one();
+        if !p.is_zero() && !q.is_identity() {
+            let mut coeffs = g2::ell_coeffs(q, TATE_LOOP_COUNT).into_iter();
+            for i in TATE_LOOP_COUNT.iter().skip(1) {
+                Fq12Config::lazy_square_in_place(&mut f);
+                Self::ell(&mut f, &coeffs.next().unwrap(), &p);
+                match i {
+                    1 | -1 => Self::ell(&mut f, &coeffs.next().unwrap(), &p),
+                    0 => continue,
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        // The Miller loop output is invertible for any pair of group elements.
+        Self::final_exponentiation(MillerLoopOutput(f)).unwrap().0
+    }
+
     // Evaluate the line function at point p.
     fn ell(f: &mut Fq12, coeffs: &g2::EllCoeff<Fq2>, p: &G1Prepared) {
         let mut c0 = coeffs.0;
@@ -32,7 +67,7 @@ impl Yafa {
         // This is a divisive twist
         c0.mul_assign_by_fp(py);
         c1.mul_assign_by_fp(px);
-        f.mul_by_034(&c0, &c1, &c2);
+        Fq12Config::lazy_mul_by_034(f, &c0, &c1, &c2);
     }
 }
 
@@ -65,19 +100,34 @@ impl Pairing for Yafa {
         let f = cfg_chunks_mut!(pairs, 4)
             .map(|pairs| {
                 let mut f = Self::TargetField::one();
-                for i in TATE_LOOP_COUNT.iter().skip(1) {
-                    f.square_in_place();
+
+                // All the loop counts have the same length, so the Miller loops at
+                // Q, psi(Q), psi^2(Q) and psi^3(Q) share all of their squarings.
+                for j in 1..ATE_LOOP_COUNTS[0].len() {
+                    Fq12Config::lazy_square_in_place(&mut f);
                     for (p, coeffs) in pairs.iter_mut() {
-                        Self::ell(&mut f, &coeffs.next().unwrap(), &p);
+                        for _ in 0..ATE_LOOP_COUNTS.len() {
+                            Self::ell(&mut f, &coeffs.next().unwrap(), p);
+                        }
                     }
-                    match i {
-                        1 | -1 => {
-                            for (p, coeffs) in pairs.iter_mut() {
-                                Self::ell(&mut f, &coeffs.next().unwrap(), &p);
+
+                    for loop_count in ATE_LOOP_COUNTS {
+                        match loop_count[j] {
+                            1 | -1 => {
+                                for (p, coeffs) in pairs.iter_mut() {
+                                    Self::ell(&mut f, &coeffs.next().unwrap(), p);
+                                }
                             }
+                            0 => continue,
+                            _ => unreachable!(),
                         }
-                        0 => continue,
-                        _ => unreachable!(),
+                    }
+                }
+
+                // The lines that combine the four Miller loops.
+                for (p, coeffs) in pairs.iter_mut() {
+                    for coeff in coeffs {
+                        Self::ell(&mut f, &coeff, p);
                     }
                 }
                 f
@@ -116,12 +166,21 @@ impl Pairing for Yafa {
             elt_q2.frobenius_map(2);
             elt_q3.frobenius_map(3);
 
-            let w3_part = elt_q3.cyclotomic_exp(&FINAL_EXPONENT_LAST_CHUNK_W3);
-            let w2_part = elt_q2.cyclotomic_exp(&FINAL_EXPONENT_LAST_CHUNK_W2);
-            let w1_part = elt_q.cyclotomic_exp(&FINAL_EXPONENT_LAST_CHUNK_W1);
-            let w0_part = r.cyclotomic_exp(&FINAL_EXPONENT_LAST_CHUNK_W0);
+            if FINAL_EXPONENT_LAST_CHUNK_W1_IS_NEG {
+                elt_q.cyclotomic_inverse_in_place();
+            }
 
-            PairingOutput(w3_part * &w2_part * &w1_part * &w0_part)
+            PairingOutput(cyclotomic_multi_exp(
+                &[r, elt_q, elt_q2, elt_q3],
+                &[
+                    FINAL_EXPONENT_LAST_CHUNK_W0,
+                    FINAL_EXPONENT_LAST_CHUNK_ABS_OF_W1,
+                    FINAL_EXPONENT_LAST_CHUNK_W2,
+                    FINAL_EXPONENT_LAST_CHUNK_W3,
+                ],
+                Fq12::cyclotomic_square_in_place,
+                Fq12Config::lazy_mul_assign,
+            ))
         })
     }
 }
@@ -129,6 +188,53 @@ impl Pairing for Yafa {
 /// TWIST = (0, 1, 0)
 pub const TWIST: Fq2 = Fq2::new(Fq::ZERO, Fq::ONE);
 
+/// The optimal ate pairing is computed from the short vector (c0, c1, c2, c3) =
+/// (ATE_LOOP_COUNT_0, ATE_LOOP_COUNT_1, ATE_LOOP_COUNT_2, ATE_LOOP_COUNT_3) of the lattice
+/// {(c0, c1, c2, c3) : c0 + c1 * q + c2 * q^2 + c3 * q^3 = 0 mod r}, as the product of
+/// f_{c_i, Q}(P)^(q^i) = f_{c_i, psi^i(Q)}(P) over i, and of the lines through
+/// [c3 * q^3]Q and [c2 * q^2]Q, and through their sum and [c1 * q]Q.
+/// See https://eprint.iacr.org/2008/096.pdf.
+///
+/// The loop counts are written in non-adjacent form, MSB first, and all have the same length.
+pub(crate) const ATE_LOOP_COUNTS: [&[i8]; 4] = [
+    ATE_LOOP_COUNT_0,
+    ATE_LOOP_COUNT_1,
+    ATE_LOOP_COUNT_2,
+    ATE_LOOP_COUNT_3,
+];
+
+/// ATE_LOOP_COUNT_0 =
+/// 14560567676187613661
+pub const ATE_LOOP_COUNT_0: &[i8] = &[
+    1, 0, -1, 0, 0, 1, 0, 1, 0, 0, 0, 0, 1, 0, 0, 1, 0, -1, 0, 0, 0, 1, 0, 0, -1, 0, 0, 1, 0, 1, 0,
+    0, 0, 0, 0, 0, 0, 0, 1, 0, -1, 0, 1, 0, -1, 0, 0, 1, 0, 1, 0, 1, 0, -1, 0, 1, 0, 0, 0, -1, 0,
+    0, -1, 0, 1,
+];
+
+/// ATE_LOOP_COUNT_1 =
+/// -16893141702810623142
+pub const ATE_LOOP_COUNT_1: &[i8] = &[
+    -1, 0, 0, 1, 0, -1, 0, -1, 0, -1, 0, 0, 1, 0, 0, 0, 0, -1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 1, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -1, 0, 1, 0, 0, 0, 1, 0, 1, 0, 1, 0, 0, 1, 0, 0, -1, 0, -1, 0,
+    -1, 0, 1, 0,
+];
+
+/// ATE_LOOP_COUNT_2 =
+/// 21683456033259836021
+pub const ATE_LOOP_COUNT_2: &[i8] = &[
+    1, 0, 1, 0, -1, 0, -1, 0, 1, 0, 0, 0, -1, 0, -1, 0, -1, 0, 0, 1, 0, 0, -1, 0, 0, 1, 0, -1, 0,
+    0, 0, 0, 1, 0, 0, -1, 0, 0, -1, 0, 1, 0, 1, 0, 0, 0, 0, 0, 1, 0, -1, 0, 1, 0, 0, 1, 0, 1, 0, 0,
+    -1, 0, 1, 0, 1,
+];
+
+/// ATE_LOOP_COUNT_3 =
+/// 16654425991786044614
+pub const ATE_LOOP_COUNT_3: &[i8] = &[
+    1, 0, 0, -1, 0, 1, 0, 0, -1, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, -1, 0, 1, 0, 0, 0, 0, 1, 0, -1, 0,
+    1, 0, 0, -1, 0, -1, 0, -1, 0, 1, 0, 1, 0, 0, -1, 0, 0, 0, 0, 1, 0, 1, 0, 0, -1, 0, 1, 0, -1, 0,
+    0, 1, 0, -1, 0,
+];
+
 /// TATE_LOOP_COUNT =
 /// 57896044618658097711785492504343953926634992332820282019728792003956564819949
 pub const TATE_LOOP_COUNT: &'static [i8] = &[
@@ -143,58 +249,70 @@ pub const TATE_LOOP_COUNT: &'static [i8] = &[
     1,
 ];
 
+/// The hard part of the final exponentiation is evaluated as
+/// w0 + w1 * q + w2 * q^2 + w3 * q^3 = FINAL_EXPONENT_MULTIPLIER * (q^4 - q^2 + 1) / r,
+/// where (w0, w1, w2, w3) is a short vector of the lattice
+/// {(w0, w1, w2, w3) : w0 + w1 * q + w2 * q^2 + w3 * q^3 = 0 mod (q^4 - q^2 + 1) / r},
+/// of about 509 bits instead of the 576 bits of the plain base-q digits.
+/// The pairing is thus the FINAL_EXPONENT_MULTIPLIER-th power of the one with the exponent
+/// (q^12 - 1) / r.
+///
+/// FINAL_EXPONENT_MULTIPLIER =
+/// 2441367137060436134714609842328221514029753801440928582250
+pub const FINAL_EXPONENT_MULTIPLIER: [u64; 3] =
+    [0xd1e0ac1b5e4dca6a, 0x285b5c1d4f658018, 0x63910feda5fba8c8];
+
 /// FINAL_EXPONENT_LAST_CHUNK_W0 =
-/// 27816679719392039590916985961594891329592754889347470862124370788722235197910030055580499179007884620636974433034081916025003956486380300643703133308392747535275498881809581
-pub const FINAL_EXPONENT_LAST_CHUNK_W0: BigInt<9> = BigInt::new([
-    0xbc41361f419dfcad,
-    0x7e898c59e9f6e67a,
-    0xa53054d2e155420a,
-    0xf01d5c325a7e8db9,
-    0x0062cc2d9828db4b,
-    0xa9ab464e0f3efb97,
-    0x4498a2a9fcde4960,
-    0xf05a7a282a9da654,
-    0x1ccaaec847ce3756,
+/// 1191077750993469918464456711038131965972165798294660056901976973381443876584945748467930455138170053763321550637702725164674235753875887476285129819497877
+pub const FINAL_EXPONENT_LAST_CHUNK_W0: BigInt<8> = BigInt::new([
+    0xb4c709ea480d2595,
+    0x55a0d07298ec3a29,
+    0x6ad5145e84dd3804,
+    0xcece7d1d2e2a82c0,
+    0x6f106bfb90e09042,
+    0xe691f8bf165c6512,
+    0x4445853c8876f49c,
+    0x16bddde4d99c0b19,
 ]);
 
-/// FINAL_EXPONENT_LAST_CHUNK_W1 =
-/// 19533734280962826151300710596734343137033362535294523292134692601750277351215937028705950010165895260665221949740604041087518331761534784981220098087966726908412430392012572
-pub const FINAL_EXPONENT_LAST_CHUNK_W1: BigInt<9> = BigInt::new([
-    0x4a8171212b68af1c,
-    0x6e9fd1b1cd44b35d,
-    0x10677198e7742d97,
-    0x13b522f9221f979a,
-    0xa9d14c2dbb4cf3db,
-    0xaed043007079fa1e,
-    0x7b9bdbddd59fe860,
-    0x0ee6a96afcb80cfc,
-    0x1437ebf93e40e187,
+/// FINAL_EXPONENT_LAST_CHUNK_W1_IS_NEG = true
+pub const FINAL_EXPONENT_LAST_CHUNK_W1_IS_NEG: bool = true;
+
+/// FINAL_EXPONENT_LAST_CHUNK_ABS_OF_W1 =
+/// 148045198215501445422402087304831799674614426231835487021045433884068120516978677289330552423638119046154832112740908840167580236555136396464560968239487
+pub const FINAL_EXPONENT_LAST_CHUNK_ABS_OF_W1: BigInt<8> = BigInt::new([
+    0xce6aaa43488b097f,
+    0x23b50a9505b14a64,
+    0xdf38c255dd2b6232,
+    0xf1e2848d46cd18db,
+    0x24886d38d9f51508,
+    0xef16b78dcdb0365a,
+    0x67b0aabda7ee1107,
+    0x02d3a13e2fef58c5,
 ]);
 
 /// FINAL_EXPONENT_LAST_CHUNK_W2 =
-/// 18787556099692390437676225874885260726423443207869755270416397806622396448189003069574413752571064036218150696957580977806429906239508024997419791567936987076281415850010664
-pub const FINAL_EXPONENT_LAST_CHUNK_W2: BigInt<9> = BigInt::new([
-    0x3e418200ad402828,
-    0xeaac6e307c80e9ac,
-    0x993fbe8fefb574c8,
-    0x7e876f3ddda8ba7a,
-    0xf8ff3fbf0bb49620,
-    0x63883dfe09f064b0,
-    0x8fd38d1af07c11e5,
-    0xee91cf069f66a1eb,
-    0x1372344f3c10e50b,
+/// 929671296096644381535163116748529471289965702976623551884285692930548338204305297926147462743276118141837681152269505564314587321541304528651897427367157
+pub const FINAL_EXPONENT_LAST_CHUNK_W2: BigInt<8> = BigInt::new([
+    0x8c2166a123de94f5,
+    0x8f04c9968f786926,
+    0x83279d1dc2e1737d,
+    0xf7b33f2960e859eb,
+    0x241c88b19a3a30bd,
+    0xc756ebe56ae06d23,
+    0x7a8be0a963eed13c,
+    0x11c0237dc23c0ca1,
 ]);
 
 /// FINAL_EXPONENT_LAST_CHUNK_W3 =
-/// 600746510796850913325441891520214792552399427644760978176030334322268158184417708567430611936132
-pub const FINAL_EXPONENT_LAST_CHUNK_W3: BigInt<9> = BigInt::new([
-    0xba0cbd2b5bed2384,
-    0xdab3b5db47bc3720,
-    0x6303cbb620293bf6,
-    0xd93c6dabba8ba44f,
-    0x4800013b93e07009,
-    0x0,
-    0x0,
-    0x0,
-    0x0,
+/// 1466642789163154299937019517896097012074713448342989082034361567985944617280182744929899425602937739604391109676299666604935008975418074149012667039814503
+pub const FINAL_EXPONENT_LAST_CHUNK_W3: BigInt<8> = BigInt::new([
+    0x7855377641191367,
+    0xa385fe659daa5181,
+    0xfe5f95afc4ce6b8f,
+    0x9c337cfb44663a1a,
+    0x1485284349bd562a,
+    0xdcde317d3c4421f6,
+    0x39d21d71305fec53,
+    0x1c00ccf593b0f78a,
 ]);
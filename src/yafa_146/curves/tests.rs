@@ -1,7 +1,477 @@
-use crate::yafa_146::*;
+use crate::{
+    glv::{
+        tests::{
+            check_clear_cofactor, check_endomorphism, check_msm, check_mul, check_subgroup_check,
+            double_and_add,
+        },
+        EndomorphismConfig,
+    },
+    hashing::tests::check_hash_to_curve,
+    yafa_146::*,
+};
 use ark_algebra_test_templates::*;
+use ark_ec::{
+    hashing::HashToCurve,
+    pairing::{Pairing, PairingOutput},
+    AffineRepr, CurveGroup,
+};
+use ark_ff::{
+    fields::fp6_3over2::Fp6Config, BigInt, BigInteger, CyclotomicMultSubgroup, Field, One,
+    PrimeField,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{test_rng, vec::Vec, UniformRand, Zero};
+use sha2::{Sha256, Sha512};
 
 test_group!(g1; G1Projective; sw);
 test_group!(g2; G2Projective; sw);
 test_group!(pairing_output; ark_ec::pairing::PairingOutput<Yafa>; msm);
 test_pairing!(pairing; crate::yafa_146::Yafa);
+
+#[test]
+fn test_optimal_ate_matches_tate() {
+    // The optimal ate pairing equals the reference Tate pairing raised to this power.
+    const EXPONENT: Fr = FrConfig::const_from_bigint(BigInt!(
+        "6929441790707748910159440998097740887757177849110958925983132529492643085158"
+    ));
+
+    let mut rng = test_rng();
+
+    for _ in 0..5 {
+        let p = G1Projective::rand(&mut rng).into_affine();
+        let q = G2Projective::rand(&mut rng).into_affine();
+
+        let tate = Yafa::tate_pairing(&p, &q);
+        assert_eq!(Yafa::pairing(p, q).0, tate.pow(EXPONENT.into_bigint()));
+    }
+}
+
+// The hard part (q^4 - q^2 + 1) / r = HARD_PART_W0 + HARD_PART_W1 * q + HARD_PART_W2 * q^2
+// + HARD_PART_W3 * q^3, without a multiplier.
+
+/// HARD_PART_W0 =
+/// 27816679719392039590916985961594891329592754889347470862124370788722235197910030055580499179007884620636974433034081916025003956486380300643703133308392747535275498881809581
+const HARD_PART_W0: BigInt<9> = BigInt::new([
+    0xbc41361f419dfcad,
+    0x7e898c59e9f6e67a,
+    0xa53054d2e155420a,
+    0xf01d5c325a7e8db9,
+    0x0062cc2d9828db4b,
+    0xa9ab464e0f3efb97,
+    0x4498a2a9fcde4960,
+    0xf05a7a282a9da654,
+    0x1ccaaec847ce3756,
+]);
+
+/// HARD_PART_W1 =
+/// 19533734280962826151300710596734343137033362535294523292134692601750277351215937028705950010165895260665221949740604041087518331761534784981220098087966726908412430392012572
+const HARD_PART_W1: BigInt<9> = BigInt::new([
+    0x4a8171212b68af1c,
+    0x6e9fd1b1cd44b35d,
+    0x10677198e7742d97,
+    0x13b522f9221f979a,
+    0xa9d14c2dbb4cf3db,
+    0xaed043007079fa1e,
+    0x7b9bdbddd59fe860,
+    0x0ee6a96afcb80cfc,
+    0x1437ebf93e40e187,
+]);
+
+/// HARD_PART_W2 =
+/// 18787556099692390437676225874885260726423443207869755270416397806622396448189003069574413752571064036218150696957580977806429906239508024997419791567936987076281415850010664
+const HARD_PART_W2: BigInt<9> = BigInt::new([
+    0x3e418200ad402828,
+    0xeaac6e307c80e9ac,
+    0x993fbe8fefb574c8,
+    0x7e876f3ddda8ba7a,
+    0xf8ff3fbf0bb49620,
+    0x63883dfe09f064b0,
+    0x8fd38d1af07c11e5,
+    0xee91cf069f66a1eb,
+    0x1372344f3c10e50b,
+]);
+
+/// HARD_PART_W3 =
+/// 600746510796850913325441891520214792552399427644760978176030334322268158184417708567430611936132
+const HARD_PART_W3: BigInt<9> = BigInt::new([
+    0xba0cbd2b5bed2384,
+    0xdab3b5db47bc3720,
+    0x6303cbb620293bf6,
+    0xd93c6dabba8ba44f,
+    0x4800013b93e07009,
+    0x0,
+    0x0,
+    0x0,
+    0x0,
+]);
+
+#[test]
+fn test_final_exponentiation_matches_hard_part() {
+    let mut rng = test_rng();
+
+    for _ in 0..5 {
+        let p = G1Projective::rand(&mut rng);
+        let q = G2Projective::rand(&mut rng);
+        let f = Yafa::miller_loop(p, q).0;
+
+        // elt = f^((q^6 - 1) * (q^2 + 1))
+        let mut elt = f;
+        elt.conjugate_in_place();
+        elt *= &f.inverse().unwrap();
+        let mut elt_q2 = elt;
+        elt_q2.frobenius_map(2);
+        elt *= &elt_q2;
+
+        // elt^((q^4 - q^2 + 1) / r)
+        let mut expected = Fq12::ONE;
+        for (i, w) in [HARD_PART_W0, HARD_PART_W1, HARD_PART_W2, HARD_PART_W3]
+            .iter()
+            .enumerate()
+        {
+            let mut elt_qi = elt;
+            elt_qi.frobenius_map(i);
+            expected *= &elt_qi.cyclotomic_exp(w);
+        }
+
+        assert_eq!(
+            Yafa::pairing(p, q).0,
+            expected.pow(FINAL_EXPONENT_MULTIPLIER)
+        );
+    }
+}
+
+#[test]
+fn test_torus_serialization() {
+    let mut rng = test_rng();
+
+    for _ in 0..5 {
+        let g = Yafa::pairing(G1Projective::rand(&mut rng), G2Projective::rand(&mut rng));
+        let torus = TorusPairingOutput::from(g);
+
+        let mut bytes = Vec::new();
+        torus.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), torus.compressed_size());
+        assert_eq!(3 * bytes.len(), g.compressed_size());
+        assert_eq!(
+            TorusPairingOutput::deserialize_compressed(&bytes[..]).unwrap(),
+            torus
+        );
+    }
+
+    let one = TorusPairingOutput::from(PairingOutput::<Yafa>::zero());
+    assert!(one.compress() == (Fq2::zero(), Fq2::zero()));
+    assert_eq!(
+        TorusPairingOutput::decompress(&Fq2::zero(), &Fq2::zero()),
+        Some(one)
+    );
+    assert_eq!(
+        TorusPairingOutput::decompress(&Fq2::zero(), &Fq2::one()),
+        None
+    );
+}
+
+#[test]
+fn test_torus_decompression() {
+    // Returns whether the norm of g from Fq12 to its subfield of degree k is 1.
+    let norm_is_one = |g: &Fq12, k: usize| {
+        let mut norm = *g;
+        for i in 1..12 / k {
+            let mut g_qi = *g;
+            g_qi.frobenius_map(k * i);
+            norm *= &g_qi;
+        }
+        norm.is_one()
+    };
+
+    // The torus coordinate a1 is never zero, as it would require a2^2 = -1 / (3 * xi).
+    let a2_square = -(Fq6Config::NONRESIDUE * &Fq2::from(3u64))
+        .inverse()
+        .unwrap();
+    assert!(a2_square.sqrt().is_none());
+
+    let mut rng = test_rng();
+
+    for _ in 0..5 {
+        let (a1, a2) = (Fq2::rand(&mut rng), Fq2::rand(&mut rng));
+        let g = TorusPairingOutput::decompress(&a1, &a2).unwrap();
+        assert!(norm_is_one(&(g.0).0, 6) && norm_is_one(&(g.0).0, 4));
+        assert_eq!(g.compress(), (a1, a2));
+
+        // Any pair with a1 != 0 encodes an element of the torus T6(Fq2), but not necessarily
+        // of GT.
+        let mut bytes = Vec::new();
+        g.serialize_compressed(&mut bytes).unwrap();
+        assert!(TorusPairingOutput::deserialize_compressed(&bytes[..]).is_err());
+        assert_eq!(
+            TorusPairingOutput::deserialize_compressed_unchecked(&bytes[..]).unwrap(),
+            g
+        );
+    }
+}
+
+#[test]
+fn test_g1_endomorphism() {
+    check_endomorphism::<g1::Parameters, 2>();
+}
+
+#[test]
+fn test_g1_glv_mul() {
+    check_mul::<g1::Parameters, 2>();
+}
+
+#[test]
+fn test_g1_glv_msm() {
+    check_msm::<g1::Parameters, 2>();
+}
+
+#[test]
+fn test_g2_psi() {
+    let mut rng = test_rng();
+    let q = Fr::from_le_bytes_mod_order(&Fq::MODULUS.to_bytes_le());
+    assert_eq!(q, g2::Q_MOD_R);
+
+    for _ in 0..5 {
+        let p = G2Projective::rand(&mut rng);
+        let psi = g2::psi(&p.into_affine());
+        assert_eq!(psi, double_and_add(&p, &q).into_affine());
+        assert_eq!(
+            <g2::Parameters as EndomorphismConfig<4>>::endomorphism(&p).into_affine(),
+            psi
+        );
+        assert_eq!(
+            <g2::Parameters as EndomorphismConfig<4>>::endomorphism_affine(&p.into_affine()),
+            psi
+        );
+    }
+    assert!(
+        <g2::Parameters as EndomorphismConfig<4>>::endomorphism_affine(&G2Affine::zero()).is_zero()
+    );
+}
+
+#[test]
+fn test_g2_gls_mul() {
+    check_mul::<g2::Parameters, 4>();
+}
+
+#[test]
+fn test_g2_gls_msm() {
+    check_msm::<g2::Parameters, 4>();
+}
+
+#[test]
+fn test_g1_clear_cofactor() {
+    check_clear_cofactor::<g1::Parameters>();
+}
+
+#[test]
+fn test_g2_clear_cofactor() {
+    check_clear_cofactor::<g2::Parameters>();
+}
+
+#[test]
+fn test_g1_subgroup_check() {
+    check_subgroup_check::<g1::Parameters>();
+}
+
+#[test]
+fn test_g2_subgroup_check() {
+    check_subgroup_check::<g2::Parameters>();
+}
+
+#[test]
+fn test_g1_hash_to_curve_sha256() {
+    check_hash_to_curve::<g1::Parameters, Sha256, 4>(
+        G1_SHA256_SUITE_ID,
+        &[
+            [
+                "10838209242635086714917534414181074095669150149462402088321598492584743280796447254849568455736870828510744621772425309601387439035609908928854149655907168538948827853721885",
+                "13295412437919265882197343735071779177688509823167045713012353489519984774597543636832556137445970759792383972965892434992250911933494768757920156637324537874542163906387725",
+                "29785459059883831553821373076958396593986753427675201154318868716055972090288571222190574189734936604045080093758415026942934333162447488916092968552756983832299061390834154",
+                "25054698221486376769584627453332108490135297475812977508013960379006571343400227858627332410467020920802228504261484257569076400272768127489014188383606259619633503517250978",
+            ],
+            [
+                "7235424148922864426390147195419352505611734565983485816513899538052732417722402013825681281573146372084473856338987928929082261652179279001440868372019188835378112127618109",
+                "34189750982334788604127642939366592326813829638193253493375985152358140396714859334970540114781712079002131536909246763398548680744487227904845589410850230558963181531020852",
+                "17498023000997894835396434295020648750460251794900541728136901319528066187511277558919466726009769107610509865178933987105521577043845649448715968611826740401704542277786048",
+                "18225740966696539989531295150634727817250189554028875202639023382751900027331046196213391285411130097228368362751391535787306149169642029565503719095454364581285510255429028",
+            ],
+            [
+                "26468172637827149639519081048630101497605081733998270544624535285896541833600387506430131230540450029464181267534305257398523337232828792321181356553842687139545734523991686",
+                "25844333788187882880505551286860373752873619418968336372564305996913670104269385572849560171641357893206461000735987738082616320464397359502280043365392550398821490784689034",
+                "19277406035021089796272279825645175411556763437855029431799334514381997140326002063310065087537163335084848340740984995757758826364228696326605183950012449453687741934424953",
+                "26445574546922226326139228787808522379852936529270830861169812361427721909624892867234651804775460235753570691841293808319239511932114179668035677476782016411690842479630906",
+            ],
+            [
+                "864160642544963645537738089885022271803560551002282491224929822700669543085220090472911699242406323680079376145356902820922321155079242418415024691066751283378348817185972",
+                "25277856565748319622573425095447238778656342463882657192467353140666751569948056665508105205048862689909113845376183587515531057033476885537708561637530146777017939644071286",
+                "15866103455352501629660793172514678428735233909703056355231408257694281441695261003166437375537693822052991264314587679083644546482324966289575787631759053025503942037704337",
+                "26706415939346839880419035735651634265362753429358215013415700874584233416036115079263485497802175794735438785888903800384109535031129350904947742768878244690052408429772433",
+            ],
+            [
+                "22214687161882926091443157597960420008029549689751725592451248837200345801108197120104239531986328918273610325246491204119833103923627332133783034475941482718110799385586818",
+                "6576065745426586592170715085627330576518420951644410431942283333729832198540041933749277834448388627552572962409724362154251015910738726914862560514875581121323255865674801",
+                "28779727650925999959869453986547939228802088077013133886481436926071875679216782459931260065781517245234217945522541869252519764984773906249180700285937283362431936731629784",
+                "4921751962445027788948364965483357232556488710821415842897479661171052037638715508455914515176770816279859200233314743068501963721129802344267401723868620672493455014859583",
+            ],
+        ],
+    );
+}
+
+#[test]
+fn test_g1_hash_to_curve_sha512() {
+    check_hash_to_curve::<g1::Parameters, Sha512, 4>(
+        G1_SHA512_SUITE_ID,
+        &[
+            [
+                "9130020167953668982105181318706137902804742617346653289094940281650753111790771471387739274360707084028095701347872488980638719065342134403078226273021866141278518594435278",
+                "30309619491230287541947479524388814166858846409249605186587584204931252469990458858046420339500182383559933442601534850845145631451774139946809837183369220916814229673453125",
+                "20057482633128810397236505921994440998291872021169460269736390550888486943151789057454131663082042432391629389550426182650644339797386037422964449774880561391114246505422283",
+                "9335078924871447769528448426638464939414112609047908549728189033827438950934775886705697797381241890987779361741378581863023367771075879831931671393953850482604078925769161",
+            ],
+            [
+                "30748000726918349537529909527076341662952220312016268146402762307458234566659413393072667616601023835496794834250947544740649477479829799928374284727666018789499555167555672",
+                "5438415285175278301796395226528996056697447535568712928304622241182196827994811756116961431397867009795149986700178011681726104700209605191250261857274452956237702979361796",
+                "28452086759307953567312368390892453521738717337301061941456960921062908390319222417079636673151021506238107150370033413037929140560863351764963556967684382943066447906685993",
+                "7891917010140617263360862718844142943953309638110069444903973042316698177659067507698119018506557114890722651889882809290558832711808730045493637277845906695507399722197355",
+            ],
+            [
+                "4503412782591526414882680145432900365825193127574048063348948449419800759516396460297080060182927649262044404089835372599918176156386537224856990759927196862800471302378169",
+                "18497609295994954429651455925713904256648590098106678052185190358099676801406330457416381367629337965419850535578846965181305266826599401940056912202776205621075497359762049",
+                "19359167824269243711759775576142933481766050871149575484776471411190957520812321573241502869693866866989731617114703447624443122063962397154580857973219855520272914386140737",
+                "17728232742959930796440796305629386766675926258629479289685422130554050209681312444662562284640851231323954016954892602301032787083457825744844583192077077254292817181321285",
+            ],
+        ],
+    );
+}
+
+#[test]
+fn test_g1_hash_to_curve_dst() {
+    let p = G1Sha256Hasher::new(b"DST-1").unwrap().hash(b"msg").unwrap();
+    assert_ne!(
+        p,
+        G1Sha256Hasher::new(b"DST-2").unwrap().hash(b"msg").unwrap()
+    );
+    assert_ne!(
+        p,
+        G1Sha512Hasher::new(b"DST-1").unwrap().hash(b"msg").unwrap()
+    );
+    assert_eq!(
+        p,
+        G1Sha256Hasher::new(b"DST-1").unwrap().hash(b"msg").unwrap()
+    );
+}
+
+#[test]
+fn test_g2_hash_to_curve_sha256() {
+    check_hash_to_curve::<g2::Parameters, Sha256, 8>(
+        G2_SHA256_SUITE_ID,
+        &[
+            [
+                "7026775602589955391495955914705426781104648631450482767201838987486925318970910632166400150579205130709203784649054003582830487124724482711614255026708646068043672457123043",
+                "20748229583270657614945827088779023292288471555441661356339766495388879269190667067784200288829492601377502417023021733460741864078828702437127191744224647112039858988170949",
+                "20907381442681203688233838734994162161460822517932788984506555647168039943229537922806514757830606008794738492340133450383948860439209035914282119825957877098799505238035271",
+                "25604248174589927046541961852248030008313673252312070790302331217988359130465838298201724823262171922692544238137311558616379765399342681930136158401065996634274151386591511",
+                "14327051657118639474797764816899974792968386334955232798733327506739084202093693948235768371145221195088108332410539637069718880820660024761480423006841971676691090828823410",
+                "15265805131373076635435421420517144565586251876043462553930821697616186140031626250510617118482402630356086484149327834633614812828191388932579009432433430353409411765748115",
+                "14954497884664228555407104322322185757703513841939333868404152119311518314209420556708116270466345783263457692302475178402244939592186921762837559201925090497596493909600622",
+                "16577226278578957250149427352638824372928502002763005742161498614741450492511466943693183172216904057768927833513745659440300236389103882021691732076112247706798249743868514",
+            ],
+            [
+                "5840353148263201074841362982301719533175215225510698679093900394493051254842044078180818293606558059317144064032236549193664077927553583893764087206878432149923116691429556",
+                "7041701714173812819031909381469297266121025985932436045358962567449503211044097832669243714479574457368409875140475253476640650367300001011135088717728247145488327685412879",
+                "13762634982984732779956957229962524135101691113693017423118324416154992724816655315688391197166199365614831128896127744765055603317656065964486319452570083488104972946484847",
+                "2400297029202124637640289265283225089011654611012354132413993188264129977268136136535404755968494590674733683606241324020204253334206235665196517675473508293049285810540216",
+                "17113715601580515085546636886740143760638176236421798555784548575248634741277634586785729567580523898235447940093790425512428514267293439608786104983808173473205157765050339",
+                "14533266930559596041204447004238054070053864770816909569184895495483666469346796206022305818035723044523285633354671038775859871919267382422365379503788370444965361056093881",
+                "12925962055592013600731703005429807832385159409624118821923624457586188708547951625456612181266223858772019210679779061979793091638907176808619078632077076250532586031700548",
+                "17285560963420814529407859593061064457895634888963863670684244084222902816590665032017725200529446662891979853045265259078092904142113578249025915720064157001462548458431657",
+            ],
+            [
+                "13173159062391735629650769508906924371160199637230268740679505824511340834274982362862791302261883928093544162159670177426990436465260716674695351398854760943395255824251177",
+                "14796293064029272726075887490751489615720969801222702638956278827917673138742839093867390180326333232572345782895126170539758202317726004766031681250198999257253255761702142",
+                "32976424162003473479376716452861758878186425774666135384731758238353755043750468175736276395060202166895615929165206128883788459051078475104778900218758035204916051711834771",
+                "13659894901568747492281548289294844846265020113133743098848022218311239626569910299777661338612746352014718136619175694251113032716659621160728936261215681567488860810387775",
+                "9892501841610442670930930712416770824368689908723422576553077526125274623265591565887562750417810436684385286603606074137179644710492270702260526615178290137056121184543563",
+                "32655156856497976541218572748421619453208504024091313938161398454478424375945415798365849955044974629891754139929585983995642221181160793805242540199245668066764809237184645",
+                "9622338112045922735022220587132295573428639154352925234261844544181598374626182180805967858163804954547544503677884821535787798028228148697130873653779636520069143894782034",
+                "34497440878138001220611417538885379779814921265372325992749071453374648226839628697372422180006443422707648484063703168262399977714166630125642804727142109224756090617129789",
+            ],
+            [
+                "23108073974996060152178771818838859922185178757569219469791324773533521432954018305285484079545428920297930331909877375045657648307859824266465806972605681603711548652106461",
+                "23747994098743533954451569552248666844614498858607706927143427819121968133566243400870135740421145074466984349401041525052076319234037341827707052872589175793659630533073915",
+                "24314094341306841053112494742385441610158991675663464804011707702491748456849150939720218707188014284926156136681869840055667586515706934317039944176500736705978584026623760",
+                "6851845712947882649441746977069685068896615561669132361617960222407056304854101746181500589231327805179743965671613134548797726085369069010036575486523060919013374292019778",
+                "30850688127579313279948401891735705762550933048462797908425924425043616210675549452896792735380159650460524794020995662108142673610695903845865360453520341472957396648681062",
+                "14909444110293949306318440413745114628414069233388620172463546567152997425633836532300881596001037979986261358727782685841720313993577517251121328891861637131738525914563015",
+                "27594000079482039132319090719035307991301637056197683603095787113068056883264640617118034301402652781878858761303600459824470728022656836608964375079372337081145614214324415",
+                "34684295056322506603971352360022931770659234803435138082925626299617936017279798040449182908931951636013449175251120490182225627468456808424895869415909737820619217924371410",
+            ],
+            [
+                "18952596463795459309076937916661932213943107498744893335954943175108718511010842204004050907282246370292376695722147449380778288153071898214943286299556062243414866625984919",
+                "34501406623871424748283514054855844869300754804248799791560205122579467108412195208740361886798021669227833972760130880104689616625065396150028713355356658405372682603983448",
+                "28744906148216164588086687118391249611820446678475608328488492809889903215598748022933000351605566854557338921740788295140679231115327859786622911708723328071421077579229849",
+                "31473155894834379253674246796952234760796665568719549996359282516146207828447697827090102266433836921590434085044152492734545184689877165215976271001883331271902680292498065",
+                "7267872172092458074092689075540547733066152724215131605763589473967071429576735610214739465918662822753877562019671326322686217312842544036377909164901048422526004161909404",
+                "26089151190767720445233405920526600905782205891410051486397756847761271096760137804504610164936943776098294576552053495055775946489394872642357516871643607702471810548626603",
+                "27991183824197431051709535837084188310754709413881403363443213203474573465379818827037160359919913858201565152959473285905333630707789676665897386898367509323697231001169697",
+                "12208144925403855917988342061124074347352664406355860528021742087197837079662206584595120499558451080950696501489923728768901147628973276451641083642366388230825933078348624",
+            ],
+        ],
+    );
+}
+
+#[test]
+fn test_g2_hash_to_curve_sha512() {
+    check_hash_to_curve::<g2::Parameters, Sha512, 8>(
+        G2_SHA512_SUITE_ID,
+        &[
+            [
+                "28260159287044619378737892581168278032208478746189752189841492168261522809827145553517897257242104682296794626145994908877853657832042671750387029535845067526592778824445015",
+                "30159759988892107809331916214915714115452337838425162491070242602766597552981582501083354645990987504107455871458432892688702679364728910877220944354924331787640734891355029",
+                "6397194293339405971980827994889884022377693304882853747790088074992974295057326840673682374783370471817305125567904430648106511275632123997871082181417178285575863715804018",
+                "33836287783017198087319911277222397313127827240710555440801204015931702196943359721120931174576351129521257852807402783943044448421427817498771575174163944791761440617258100",
+                "4184544584037887054488102804654131319055496510785721842097705711177858022096991422553851308620087248682520773312275111805557140486972791307801921050134432468931949641784614",
+                "16742005182627791678958380229123124783726339856165648307073091083097536952690005718614498200293943926963410545642306491890189948627305898356045246217276376651983916727588455",
+                "1060154344364661054126891236288279641696750523744107957027678130117202062254963608171259543835707459941220034293104799333203907743206841640975372779718324754693594280955431",
+                "5166198055582005121307108785916989011117109373936521535116427258292670518202026434108033725902847269084308461870500630306057679725348261511758591238884068354203247176792336",
+            ],
+            [
+                "20961810625261202882559274489871249539518596334288072177605428433570972857516776833647652101234025396379452930071695688715949713694212907231834485522876583726900010445853255",
+                "12047304750701904308503676531564484007695852057318316229893101944180563560335223550768314159919126562791568982210204604099632692024622836447534215781782161435928891936575856",
+                "14589476894891990833286023952749412878988215641728165643347656961593130740517343202210954495256865469747411425269959866136817806673622281287643096110091623488857222990520821",
+                "21074008944641997530066186686426578620296663615723111783473384524654318565926917215417307999088845941166593732857520874472042464423655317408326132249136311563381267786680841",
+                "27657863694357940143079802866532537066933056926205234946427500960323184873439456994096058684548175958782372221035799491022429081895720304137503717630960623829173252227847775",
+                "4404197701662831184640675894662042871655428116186668565067379425727600821463575891959793897818091907202756543233951112960618669044672170000597453195486225874339580921304193",
+                "11662632818371584674457816556361748221165979673073854930350796320433997487330316551855068530516568048004970750473432005167257473233846293665258166866570465873909279841329535",
+                "18085655045558268569773847095027945337260300089366860569827342491920301588735039440754295662499597985163703158823558506495591231822047303816425668099664572292148606880065097",
+            ],
+            [
+                "29373486958343331151169796324063329797580527353957844954041578109400638245139975046918573347039901071512202814172727243125546754043211708889645351733114180873725902941766463",
+                "742259522603606411281968697532773812978332195216084969450742737891965465132404496580794309631034123204928711723013308765931434499236438047226227918341342232119422473626471",
+                "11532448415488351884254682648257067558244854175694964523742958794703794646699291427928887975304181972790854738287678510694504728102238601779718242046112572082259937689880213",
+                "18868818844660193621402054624176776664162023778531800873242070494414430015427436599356684465705236995864870239404487147795592191662888807827479000524739726113264977650006693",
+                "7585855132976947030906706327998869739039092920008098726482026768245614345294313682351948104389425116102045327535689399663785129907726605807482573016914893743558022942730724",
+                "25719289793147971207691053488155466164416747295389718290004528222413197620225578035238618994898283334056024103737646782812774559834839991486350647668565696020643097934827999",
+                "31358072008916418774093853884455218299992189342924993531782667529292291719352595175946447743731420197935414477800727521259513697144004986096603759815954203583925460959928804",
+                "20988278840527882901617954908798146399624293990108909452858066678202250310191392902482800089310585440492990515233871522449752587289744197219431402673911099457016446130581931",
+            ],
+        ],
+    );
+}
+
+#[test]
+fn test_g2_hash_to_curve_dst() {
+    let p = G2Sha256Hasher::new(b"DST-1").unwrap().hash(b"msg").unwrap();
+    assert_ne!(
+        p,
+        G2Sha256Hasher::new(b"DST-2").unwrap().hash(b"msg").unwrap()
+    );
+    assert_ne!(
+        p,
+        G2Sha512Hasher::new(b"DST-1").unwrap().hash(b"msg").unwrap()
+    );
+    assert_eq!(
+        p,
+        G2Sha256Hasher::new(b"DST-1").unwrap().hash(b"msg").unwrap()
+    );
+}
@@ -0,0 +1,110 @@
+use ark_ec::pairing::PairingOutput;
+use ark_ff::{
+    fields::{fp6_3over2::Fp6Config, Fp12Config},
+    Field, One, PrimeField, Zero,
+};
+use ark_serialize::*;
+
+use crate::yafa_146::{Fq12, Fq12Config, Fq2, Fq6, Fq6Config, Fr, Yafa};
+
+/// A pairing output that is serialized in the torus T6(Fq2), at a third of the size of an
+/// element of Fq12.
+///
+/// Every element g = c0 + c1 * w of GT other than 1 has norm 1 over Fq6, so that
+/// g = (a + w) / (a - w) with a = (1 + c0) / c1. Writing a = a0 + a1 * v + a2 * v^2, g also
+/// has norm 1 over Fq4 = Fq2(w^3) exactly if 3 * (a0 * a1 - xi * a2^2) = 1, where xi = v^3.
+/// Since -1 / (3 * xi) is not a square in Fq2, a1 is never zero, so the pair (a1, a2)
+/// determines a0 and is what gets serialized. The identity is encoded as (0, 0).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TorusPairingOutput(pub PairingOutput<Yafa>);
+
+impl TorusPairingOutput {
+    /// Returns the torus coordinates (a1, a2) of the pairing output, which must be in GT.
+    pub fn compress(&self) -> (Fq2, Fq2) {
+        let g = &(self.0).0;
+        g.c1.inverse()
+            .map(|c1_inv| {
+                let a = (g.c0 + &Fq6::one()) * &c1_inv;
+                (a.c1, a.c2)
+            })
+            .unwrap_or_else(|| (Fq2::zero(), Fq2::zero()))
+    }
+
+    /// Recovers the element of the torus T6(Fq2) with the torus coordinates (a1, a2), or 1 if
+    /// both are zero. Returns `None` if only a1 is zero, since this encodes nothing.
+    ///
+    /// The result is only in GT if the coordinates are the output of [`Self::compress`].
+    pub fn decompress(a1: &Fq2, a2: &Fq2) -> Option<Self> {
+        let a1_inv = match a1.inverse() {
+            Some(a1_inv) => a1_inv,
+            None => return a2.is_zero().then(|| Self(PairingOutput(Fq12::one()))),
+        };
+
+        // a0 = (xi * a2^2 + 1 / 3) / a1
+        let three_inv = Fq2::from(3u64).inverse().unwrap();
+        let a0 = (Fq6Config::mul_fp2_by_nonresidue(a2.square()) + &three_inv) * &a1_inv;
+        let a = Fq6::new(a0, *a1, *a2);
+
+        // (a + w) / (a - w) = ((a^2 + w^2) + 2 * a * w) / (a^2 - w^2), where a^2 - w^2 is not
+        // zero because w^2 = v is not a square in Fq6.
+        let a_square = a.square();
+        let denom_inv = (a_square - &Fq12Config::NONRESIDUE).inverse().unwrap();
+        let c0 = (a_square + &Fq12Config::NONRESIDUE) * &denom_inv;
+        let c1 = a.double() * &denom_inv;
+        Some(Self(PairingOutput(Fq12::new(c0, c1))))
+    }
+}
+
+impl From<PairingOutput<Yafa>> for TorusPairingOutput {
+    fn from(g: PairingOutput<Yafa>) -> Self {
+        Self(g)
+    }
+}
+
+impl From<TorusPairingOutput> for PairingOutput<Yafa> {
+    fn from(g: TorusPairingOutput) -> Self {
+        g.0
+    }
+}
+
+impl CanonicalSerialize for TorusPairingOutput {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        let (a1, a2) = self.compress();
+        a1.serialize_with_mode(&mut writer, compress)?;
+        a2.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        2 * Fq2::zero().serialized_size(compress)
+    }
+}
+
+impl Valid for TorusPairingOutput {
+    fn check(&self) -> Result<(), SerializationError> {
+        // GT is the only subgroup of order r of the cyclic group Fq12^*.
+        match (self.0).0.pow(Fr::MODULUS).is_one() {
+            true => Ok(()),
+            false => Err(SerializationError::InvalidData),
+        }
+    }
+}
+
+impl CanonicalDeserialize for TorusPairingOutput {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let a1 = Fq2::deserialize_with_mode(&mut reader, compress, validate)?;
+        let a2 = Fq2::deserialize_with_mode(&mut reader, compress, validate)?;
+        let g = Self::decompress(&a1, &a2).ok_or(SerializationError::InvalidData)?;
+        if let Validate::Yes = validate {
+            g.check()?;
+        }
+        Ok(g)
+    }
+}
@@ -1,7 +1,177 @@
-use ark_ff::fields::{Fp576, MontBackend, MontConfig};
+use ark_ff::{
+    fields::{Fp576, MontBackend, MontConfig},
+    BigInt, Field, MontFp, Zero,
+};
 
+use crate::safegcd;
+
+pub struct FqConfig;
+pub type Fq = Fp576<MontBackend<FqConfig, 9>>;
+
+impl MontConfig<9> for FqConfig {
+    const MODULUS: BigInt<9> = BigInt!(
+        "34780846793597649115902360759330114765953831037762036596538890219938834838634659810167270279641323601509886108333904651602464968545001642013075102043510458751533543897428201"
+    );
+
+    const GENERATOR: Fq = MontFp!("59");
+
+    /// GENERATOR^t, where q - 1 = 2^3 * t
+    const TWO_ADIC_ROOT_OF_UNITY: Fq = MontFp!("14771414658235299587243000616498295660989623410560822119845798691774682881706817748242389506706882222835326926197545385465006126037907296082401316084053793975950423549296127");
+
+    #[inline(always)]
+    fn add_assign(a: &mut Fq, b: &Fq) {
+        let mut c = unrolled(a);
+        FqUnrolledConfig::add_assign(&mut c, &unrolled(b));
+        a.0 = c.0;
+    }
+
+    #[inline(always)]
+    fn sub_assign(a: &mut Fq, b: &Fq) {
+        let mut c = unrolled(a);
+        FqUnrolledConfig::sub_assign(&mut c, &unrolled(b));
+        a.0 = c.0;
+    }
+
+    #[inline(always)]
+    fn double_in_place(a: &mut Fq) {
+        let mut c = unrolled(a);
+        FqUnrolledConfig::double_in_place(&mut c);
+        a.0 = c.0;
+    }
+
+    #[inline(always)]
+    fn neg_in_place(a: &mut Fq) {
+        let mut c = unrolled(a);
+        FqUnrolledConfig::neg_in_place(&mut c);
+        a.0 = c.0;
+    }
+
+    #[inline(always)]
+    fn mul_assign(a: &mut Fq, b: &Fq) {
+        let mut c = unrolled(a);
+        FqUnrolledConfig::mul_assign(&mut c, &unrolled(b));
+        a.0 = c.0;
+    }
+
+    #[inline(always)]
+    fn square_in_place(a: &mut Fq) {
+        let mut c = unrolled(a);
+        FqUnrolledConfig::square_in_place(&mut c);
+        a.0 = c.0;
+    }
+
+    fn sum_of_products<const M: usize>(a: &[Fq; M], b: &[Fq; M]) -> Fq {
+        let (a, b) = (a.map(|a| unrolled(&a)), b.map(|b| unrolled(&b)));
+        Fq::new_unchecked(FqUnrolledConfig::sum_of_products(&a, &b).0)
+    }
+
+    /// Inverts in constant time with safegcd. The inverse of a = x * R in Montgomery form is
+    /// x^(-1) * R = a^(-1) * R^2, so R^2 is passed as the scale.
+    fn inverse(a: &Fq) -> Option<Fq> {
+        if a.is_zero() {
+            return None;
+        }
+        Some(Fq::new_unchecked(safegcd::inverse(
+            &a.0,
+            &Self::R2,
+            &SAFEGCD_MODULUS,
+        )))
+    }
+}
+
+/// A twin of [`FqConfig`] with the same modulus. Overriding `inverse` means writing out the
+/// `MontConfig` of [`FqConfig`] by hand, which would lose the unrolled arithmetic that
+/// `#[derive(MontConfig)]` generates, so [`FqConfig`] forwards all but `inverse` to this one.
 #[derive(MontConfig)]
 #[modulus = "34780846793597649115902360759330114765953831037762036596538890219938834838634659810167270279641323601509886108333904651602464968545001642013075102043510458751533543897428201"]
 #[generator = "59"]
-pub struct FqConfig;
-pub type Fq = Fp576<MontBackend<FqConfig, 9>>;
+struct FqUnrolledConfig;
+type FqUnrolled = Fp576<MontBackend<FqUnrolledConfig, 9>>;
+
+/// Reinterprets the Montgomery form of `a` as an element of [`FqUnrolled`].
+#[inline(always)]
+const fn unrolled(a: &Fq) -> FqUnrolled {
+    FqUnrolled::new_unchecked(a.0)
+}
+
+impl FqConfig {
+    /// Computes a square root of `fe`, or returns `None` if `fe` is not a square.
+    ///
+    /// Since q = 9 (mod 16), a square root of a square x is x^((q + 7) / 16) times one of
+    /// [`SQRT_MULTIPLIERS`], the square roots of the fourth roots of unity. This is the method
+    /// of RFC 9380, Appendix I.3, which needs a single exponentiation.
+    pub fn sqrt(fe: &Fq) -> Option<Fq> {
+        let t = fe.pow(MODULUS_MINUS_NINE_DIV_SIXTEEN) * fe;
+        SQRT_MULTIPLIERS
+            .iter()
+            .map(|c| t * c)
+            .find(|root| root.square() == *fe)
+    }
+
+    /// Same as [`Self::sqrt`], but in constant time, up to the timing of the arithmetic in Fq.
+    pub fn sqrt_ct(fe: &Fq) -> Option<Fq> {
+        let t = fe.pow(MODULUS_MINUS_NINE_DIV_SIXTEEN) * fe;
+        let (is_square, root) = Self::find_sqrt_ct(&t, fe);
+        (is_square == 1).then_some(root)
+    }
+
+    /// Returns 1 and the first of the `t * SQRT_MULTIPLIERS[i]` that squares to `fe`, or 0 and
+    /// zero if there is none, in constant time.
+    pub(crate) fn find_sqrt_ct(t: &Fq, fe: &Fq) -> (u64, Fq) {
+        let (mut found, mut res) = (0, Fq::ZERO);
+        for c in SQRT_MULTIPLIERS.iter() {
+            let root = *t * c;
+            let is_root = Self::ct_eq(&root.square(), fe) & !found;
+            res = Self::ct_select(&res, &root, is_root);
+            found |= is_root;
+        }
+        (found, res)
+    }
+
+    /// Returns 1 if `a == b` and 0 otherwise, without branching on their limbs.
+    pub(crate) fn ct_eq(a: &Fq, b: &Fq) -> u64 {
+        let diff = (a.0)
+            .0
+            .iter()
+            .zip((b.0).0.iter())
+            .fold(0, |acc, (a, b)| acc | (a ^ b));
+        ((diff | diff.wrapping_neg()) >> 63) ^ 1
+    }
+
+    /// Returns `b` if `choice` is 1 and `a` if it is 0, without branching on `choice`.
+    pub(crate) fn ct_select(a: &Fq, b: &Fq, choice: u64) -> Fq {
+        let mask = choice.wrapping_neg();
+        let mut res = *a;
+        for (res, b) in (res.0).0.iter_mut().zip((b.0).0.iter()) {
+            *res ^= mask & (*res ^ b);
+        }
+        res
+    }
+}
+
+/// MODULUS_MINUS_NINE_DIV_SIXTEEN = (q - 9) / 16 =
+/// 2173802924599853069743897547458132172872114439860127287283680638746177177414666238135454392477582725094367881770869040725154060534062602625817193877719403671970846493589262
+pub(crate) const MODULUS_MINUS_NINE_DIV_SIXTEEN: [u64; 9] = [
+    0x808158152f773f0e,
+    0xddf6ac56be235515,
+    0x11495b477826bab3,
+    0x725a8234845d1337,
+    0xb05064729b44e410,
+    0xb6d59daeda3de1b8,
+    0x7b181e5db10149df,
+    0x4ec9e36d5dd45d22,
+    0x02400009dc9f0380,
+];
+
+/// SQRT_MULTIPLIERS = [1, sqrt(-1), sqrt(sqrt(-1)), sqrt(-sqrt(-1))], where
+/// sqrt(-1) =
+/// 23873584491135361558141770676230788432648086588430612176726394628413105195110808381146761836613718137725682296182185556337884734496101648885678967963032491527113094569368322
+pub(crate) const SQRT_MULTIPLIERS: [Fq; 4] = [
+    Fq::ONE,
+    MontFp!("23873584491135361558141770676230788432648086588430612176726394628413105195110808381146761836613718137725682296182185556337884734496101648885678967963032491527113094569368322"),
+    MontFp!("21155744299485392972608576631344817586265930466124250508361204862759504641292691328486173713597603461261159669902389793686820496115355234766051534449860375670015480266700865"),
+    MontFp!("20009432135362349528659360142831819104964207627201214476693091528164151956927842061924880772934441378674559182136359266137458842507094345930673785959456664775583120348132074"),
+];
+
+/// The modulus q in the representation of [`safegcd`]
+const SAFEGCD_MODULUS: safegcd::Modulus = safegcd::Modulus::new(&FqConfig::MODULUS);
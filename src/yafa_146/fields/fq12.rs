@@ -1,5 +1,8 @@
-use crate::yafa_146::*;
-use ark_ff::{fields::*, MontFp};
+use crate::{
+    lazy::{add, sub, FqWide},
+    yafa_146::*,
+};
+use ark_ff::{fields::*, MontFp, Zero};
 
 pub type Fq12 = Fp12<Fq12Config>;
 
@@ -71,3 +74,112 @@ impl Fp12Config for Fq12Config {
         ),
     ];
 }
+
+impl Fq12Config {
+    /// Multiplies `fe` by `other` with lazy reduction, using 54 products in Fq and 12
+    /// reductions instead of 54 multiplications in Fq.
+    pub fn lazy_mul_assign(fe: &mut Fq12, other: &Fq12) {
+        let v0 = Fq6Config::mul_wide(&fe.c0, &other.c0);
+        let v1 = Fq6Config::mul_wide(&fe.c1, &other.c1);
+        let v2 = Fq6Config::mul_wide(&(fe.c0 + &fe.c1), &(other.c0 + &other.c1));
+
+        fe.c0 = Fq6Config::reduce(&add(v0, mul_fp6_wide_by_nonresidue(v1)));
+        fe.c1 = Fq6Config::reduce(&sub(sub(v2, v0), v1));
+    }
+
+    /// Squares `fe` in place with lazy reduction, using the complex method with 36 products
+    /// in Fq and 12 reductions.
+    pub fn lazy_square_in_place(fe: &mut Fq12) -> &mut Fq12 {
+        // c0 = (a0 + a1) * (a0 + v * a1) - (1 + v) * a0 * a1, c1 = 2 * a0 * a1
+        let v0 = Fq6Config::mul_wide(&fe.c0, &fe.c1);
+        let mut a1_v = fe.c1;
+        Self::mul_fp6_by_nonresidue_in_place(&mut a1_v);
+        let v1 = Fq6Config::mul_wide(&(fe.c0 + &fe.c1), &(fe.c0 + &a1_v));
+
+        fe.c0 = Fq6Config::reduce(&sub(sub(v1, v0), mul_fp6_wide_by_nonresidue(v0)));
+        fe.c1 = Fq6Config::reduce(&add(v0, v0));
+        fe
+    }
+
+    /// Multiplies `fe` by the sparse element `c0 + (c3 + c4 * v) * w` with lazy reduction,
+    /// using 39 products in Fq and 12 reductions. This computes the same as
+    /// [`Fq12::mul_by_034`].
+    pub fn lazy_mul_by_034(fe: &mut Fq12, c0: &Fq2, c3: &Fq2, c4: &Fq2) {
+        let a = Fq6Config::mul_by_fp2_wide(&fe.c0, c0);
+        let b = Fq6Config::mul_by_01_wide(&fe.c1, c3, c4);
+        let e = Fq6Config::mul_by_01_wide(&(fe.c0 + &fe.c1), &(*c0 + c3), c4);
+
+        fe.c0 = Fq6Config::reduce(&add(a, mul_fp6_wide_by_nonresidue(b)));
+        fe.c1 = Fq6Config::reduce(&sub(sub(e, a), b));
+    }
+
+    /// Compresses `fe`, which must be in the cyclotomic subgroup of order q^4 - q^2 + 1, to
+    /// (g2, g3, g4, g5), from which it is recovered by [`Self::cyclotomic_decompress`]. Here
+    /// fe = g0 + g2 * w + g4 * w^2 + g1 * w^3 + g3 * w^4 + g5 * w^5, where w^2 = v.
+    pub fn cyclotomic_compress(fe: &Fq12) -> [Fq2; 4] {
+        [fe.c1.c0, fe.c0.c2, fe.c0.c1, fe.c1.c2]
+    }
+
+    /// Squares a compressed element in place, with 4 multiplications in Fq2, using the method
+    /// of Karabina, "Squaring in Cyclotomic Subgroups", https://eprint.iacr.org/2010/542.pdf.
+    ///
+    /// This is faster than [`ark_ff::CyclotomicMultSubgroup::cyclotomic_square_in_place`], but
+    /// decompression needs an inversion, so it only pays off for long runs of squarings.
+    pub fn compressed_square_in_place(g: &mut [Fq2; 4]) -> &mut [Fq2; 4] {
+        let fp2_nr = Fq6Config::mul_fp2_by_nonresidue;
+        let [g2, g3, g4, g5] = *g;
+
+        // b45 = g4 * g5, a45 = (g4 + g5) * (g4 + xi * g5)
+        let b45 = g4 * &g5;
+        let a45 = (g4 + &g5) * &(g4 + &fp2_nr(g5));
+        // b23 = g2 * g3, a23 = (g2 + g3) * (g2 + xi * g3)
+        let b23 = g2 * &g3;
+        let a23 = (g2 + &g3) * &(g2 + &fp2_nr(g3));
+
+        // h2 = 2 * (g2 + 3 * xi * b45)
+        let tmp = fp2_nr(b45);
+        g[0] = (g2 + &tmp.double() + &tmp).double();
+        // h3 = 3 * (a45 - (xi + 1) * b45) - 2 * g3
+        let tmp = a45 - &(fp2_nr(b45) + &b45);
+        g[1] = tmp.double() + &tmp - &g3.double();
+        // h4 = 3 * (a23 - (xi + 1) * b23) - 2 * g4
+        let tmp = a23 - &(fp2_nr(b23) + &b23);
+        g[2] = tmp.double() + &tmp - &g4.double();
+        // h5 = 2 * (g5 + 3 * b23)
+        g[3] = (g5 + &b23.double() + &b23).double();
+
+        g
+    }
+
+    /// Recovers an element of the cyclotomic subgroup from its compressed form.
+    pub fn cyclotomic_decompress(g: &[Fq2; 4]) -> Fq12 {
+        let fp2_nr = Fq6Config::mul_fp2_by_nonresidue;
+        let [g2, g3, g4, g5] = *g;
+
+        let g1 = if !g2.is_zero() {
+            // g1 = (xi * g5^2 + 3 * g4^2 - 2 * g3) / (4 * g2)
+            let g4_square = g4.square();
+            let num = fp2_nr(g5.square()) + &g4_square.double() + &g4_square - &g3.double();
+            num * &g2.double().double().inverse().unwrap()
+        } else {
+            // g1 = 2 * g4 * g5 / g3, or 0 if g3 = 0 as well
+            g3.inverse()
+                .map(|g3_inv| (g4 * &g5).double() * &g3_inv)
+                .unwrap_or_else(Fq2::zero)
+        };
+
+        // g0 = xi * (2 * g1^2 + g2 * g5 - 3 * g3 * g4) + 1
+        let g3_g4 = g3 * &g4;
+        let g0 =
+            fp2_nr(g1.square().double() + &(g2 * &g5) - &(g3_g4.double() + &g3_g4)) + &Fq2::ONE;
+
+        Fq12::new(Fq6::new(g0, g4, g3), Fq6::new(g2, g1, g5))
+    }
+}
+
+/// Multiplies an unreduced element of Fq6 by NONRESIDUE = v, where v^3 = 7 * u.
+#[inline]
+fn mul_fp6_wide_by_nonresidue(fe: [FqWide<FqConfig>; 6]) -> [FqWide<FqConfig>; 6] {
+    let [c2_0, c2_1] = Fq6Config::mul_fp2_wide_by_nonresidue([fe[4], fe[5]]);
+    [c2_0, c2_1, fe[0], fe[1], fe[2], fe[3]]
+}
@@ -1,6 +1,6 @@
-use ark_ff::{fields::*, MontFp};
+use ark_ff::{fields::*, MontFp, Zero};
 
-use crate::yafa_146::*;
+use crate::{lazy::FqWide, yafa_146::*};
 
 pub type Fq2 = Fp2<Fq2Config>;
 
@@ -20,3 +20,99 @@ impl Fp2Config for Fq2Config {
         MontFp!("-1"),
     ];
 }
+
+impl Fq2Config {
+    /// Returns the product of `a` and `b` with unreduced coefficients, from 3 products in Fq
+    /// with Karatsuba multiplication.
+    #[inline]
+    pub(crate) fn mul_wide(a: &Fq2, b: &Fq2) -> [FqWide<FqConfig>; 2] {
+        let v0 = FqWide::mul(&a.c0, &b.c0);
+        let v1 = FqWide::mul(&a.c1, &b.c1);
+        let v2 = FqWide::mul(&(a.c0 + &a.c1), &(b.c0 + &b.c1));
+        // NONRESIDUE = 17
+        [v0 + v1.mul_by_u64(17), v2 - v0 - v1]
+    }
+
+    /// Returns the square of `a` with unreduced coefficients, from 3 products in Fq.
+    #[inline]
+    pub(crate) fn square_wide(a: &Fq2) -> [FqWide<FqConfig>; 2] {
+        let v0 = FqWide::mul(&a.c0, &a.c0);
+        let v1 = FqWide::mul(&a.c1, &a.c1);
+        [v0 + v1.mul_by_u64(17), FqWide::mul(&a.c0.double(), &a.c1)]
+    }
+
+    /// Computes a square root of `fe`, or returns `None` if `fe` is not a square.
+    ///
+    /// This is the complex method of https://eprint.iacr.org/2012/685.pdf (algorithm 8). With
+    /// alpha = sqrt(c0^2 - 17 * c1^2), one of delta = (c0 + alpha) / 2 and 17 * delta is a
+    /// square in Fq, and [`sqrt_or_nonresidue`] finds out which with a single exponentiation.
+    /// So this takes two exponentiations in Fq instead of three.
+    pub fn sqrt(fe: &Fq2) -> Option<Fq2> {
+        let alpha = match fe.c1.is_zero() {
+            true => fe.c0,
+            false => FqConfig::sqrt(&fe.norm())?,
+        };
+        let delta = (fe.c0 + &alpha) * &TWO_INV;
+        let (delta_is_square, s) = sqrt_or_nonresidue(&delta);
+        // s is zero only if fe is.
+        let s_inv = s.inverse().unwrap_or(Fq::ZERO);
+        let c1_div_2s = fe.c1 * &TWO_INV * &s_inv;
+        match delta_is_square {
+            true => Some(Fq2::new(s, c1_div_2s)),
+            false => Some(Fq2::new(Self::NONRESIDUE * &c1_div_2s, delta * &s_inv)),
+        }
+    }
+
+    /// Same as [`Self::sqrt`], but in constant time, up to the timing of the arithmetic in Fq.
+    pub fn sqrt_ct(fe: &Fq2) -> Option<Fq2> {
+        let (norm_is_square, alpha) = sqrt_or_nonresidue(&fe.norm());
+        // If c1 = 0, the norm is c0^2, and alpha = c0 keeps delta non-zero unless fe = 0.
+        let alpha = FqConfig::ct_select(&alpha, &fe.c0, FqConfig::ct_eq(&fe.c1, &Fq::ZERO));
+        let delta = (fe.c0 + &alpha) * &TWO_INV;
+        let (delta_is_square, s) = sqrt_or_nonresidue(&delta);
+        // s^(q - 2) = 1 / s, or 0 if s = 0
+        let s_inv = s.pow(MODULUS_MINUS_TWO);
+        let c1_div_2s = fe.c1 * &TWO_INV * &s_inv;
+        let c0 = FqConfig::ct_select(&(Self::NONRESIDUE * &c1_div_2s), &s, delta_is_square as u64);
+        let c1 = FqConfig::ct_select(&(delta * &s_inv), &c1_div_2s, delta_is_square as u64);
+        norm_is_square.then_some(Fq2::new(c0, c1))
+    }
+}
+
+/// Returns whether `fe` is a square in Fq, along with a square root of `fe` if it is, and of
+/// 17 * fe otherwise, in constant time. Since q = 9 (mod 16), both roots are found as in
+/// [`FqConfig::sqrt`] from the same exponentiation, as
+/// (17 * fe)^((q + 7) / 16) = NONRESIDUE_TO_SQRT_EXPONENT * fe^((q + 7) / 16).
+fn sqrt_or_nonresidue(fe: &Fq) -> (bool, Fq) {
+    let t = fe.pow(MODULUS_MINUS_NINE_DIV_SIXTEEN) * fe;
+    let (is_square, root) = FqConfig::find_sqrt_ct(&t, fe);
+    let (_, nonresidue_root) = FqConfig::find_sqrt_ct(
+        &(t * &NONRESIDUE_TO_SQRT_EXPONENT),
+        &(Fq2Config::NONRESIDUE * fe),
+    );
+    (
+        is_square == 1,
+        FqConfig::ct_select(&nonresidue_root, &root, is_square),
+    )
+}
+
+/// TWO_INV = 1 / 2 = (q + 1) / 2 =
+/// 17390423396798824557951180379665057382976915518881018298269445109969417419317329905083635139820661800754943054166952325801232484272500821006537551021755229375766771948714101
+const TWO_INV: Fq = MontFp!("17390423396798824557951180379665057382976915518881018298269445109969417419317329905083635139820661800754943054166952325801232484272500821006537551021755229375766771948714101");
+
+/// NONRESIDUE_TO_SQRT_EXPONENT = 17^((q + 7) / 16) =
+/// 10371760133568903193391262084692870601000583659287096544408854963784833046785386385985559776243278008468688261664609777251173375230916029013100756382546713052637423085824980
+const NONRESIDUE_TO_SQRT_EXPONENT: Fq = MontFp!("10371760133568903193391262084692870601000583659287096544408854963784833046785386385985559776243278008468688261664609777251173375230916029013100756382546713052637423085824980");
+
+/// MODULUS_MINUS_TWO = q - 2
+const MODULUS_MINUS_TWO: [u64; 9] = [
+    0x08158152f773f0e7,
+    0xdf6ac56be2355158,
+    0x1495b477826bab3d,
+    0x25a8234845d13371,
+    0x05064729b44e4107,
+    0x6d59daeda3de1b8b,
+    0xb181e5db10149dfb,
+    0xec9e36d5dd45d227,
+    0x2400009dc9f03804,
+];
@@ -1,4 +1,7 @@
-use crate::yafa_146::{Fq, Fq2, Fq2Config};
+use crate::{
+    lazy::{self, add, sub, FqWide},
+    yafa_146::{Fq, Fq2, Fq2Config, FqConfig},
+};
 use ark_ff::{
     fields::fp6_3over2::{Fp6, Fp6Config},
     Field, Fp2, MontFp,
@@ -70,3 +73,91 @@ impl Fp6Config for Fq6Config {
         ),
     ];
 }
+
+impl Fq6Config {
+    /// Squares `fe` in place with lazy reduction, using the method CH-SQR2 of Chung and
+    /// Hasan, "Asymmetric Squaring Formulae", with 15 products in Fq and 6 reductions.
+    pub fn lazy_square_in_place(fe: &mut Fq6) -> &mut Fq6 {
+        let nr = Self::mul_fp2_wide_by_nonresidue;
+        let (a0, a1, a2) = (&fe.c0, &fe.c1, &fe.c2);
+
+        let s0 = Fq2Config::square_wide(a0);
+        let s1 = Fq2Config::mul_wide(&a0.double(), a1);
+        let s2 = Fq2Config::square_wide(&(*a0 - a1 + a2));
+        let s3 = Fq2Config::mul_wide(&a1.double(), a2);
+        let s4 = Fq2Config::square_wide(a2);
+
+        let c0 = add(s0, nr(s3));
+        let c1 = add(s1, nr(s4));
+        let c2 = sub(sub(add(add(s1, s2), s3), s0), s4);
+        *fe = Self::reduce(&concat(c0, c1, c2));
+        fe
+    }
+
+    /// Returns the product of `a` and `b` with unreduced coefficients, from 18 products in Fq
+    /// with Karatsuba multiplication.
+    #[inline]
+    pub(crate) fn mul_wide(a: &Fq6, b: &Fq6) -> [FqWide<FqConfig>; 6] {
+        let (mul, nr) = (Fq2Config::mul_wide, Self::mul_fp2_wide_by_nonresidue);
+
+        let v0 = mul(&a.c0, &b.c0);
+        let v1 = mul(&a.c1, &b.c1);
+        let v2 = mul(&a.c2, &b.c2);
+
+        let c0 = add(
+            v0,
+            nr(sub(sub(mul(&(a.c1 + &a.c2), &(b.c1 + &b.c2)), v1), v2)),
+        );
+        let c1 = add(
+            sub(sub(mul(&(a.c0 + &a.c1), &(b.c0 + &b.c1)), v0), v1),
+            nr(v2),
+        );
+        let c2 = add(sub(sub(mul(&(a.c0 + &a.c2), &(b.c0 + &b.c2)), v0), v2), v1);
+        concat(c0, c1, c2)
+    }
+
+    /// Returns the product of `a` and the sparse element `c0 + c1 * v` with unreduced
+    /// coefficients, from 15 products in Fq.
+    #[inline]
+    pub(crate) fn mul_by_01_wide(a: &Fq6, c0: &Fq2, c1: &Fq2) -> [FqWide<FqConfig>; 6] {
+        let mul = Fq2Config::mul_wide;
+
+        let a_a = mul(&a.c0, c0);
+        let b_b = mul(&a.c1, c1);
+
+        let t0 = add(a_a, Self::mul_fp2_wide_by_nonresidue(mul(&a.c2, c1)));
+        let t1 = sub(sub(mul(&(a.c0 + &a.c1), &(*c0 + c1)), a_a), b_b);
+        let t2 = add(mul(&a.c2, c0), b_b);
+        concat(t0, t1, t2)
+    }
+
+    /// Returns the product of `a` and `c0` with unreduced coefficients, from 9 products in Fq.
+    #[inline]
+    pub(crate) fn mul_by_fp2_wide(a: &Fq6, c0: &Fq2) -> [FqWide<FqConfig>; 6] {
+        let mul = Fq2Config::mul_wide;
+        concat(mul(&a.c0, c0), mul(&a.c1, c0), mul(&a.c2, c0))
+    }
+
+    /// Multiplies an unreduced element of Fq2 by NONRESIDUE = 7 * u, where u^2 = 17.
+    #[inline]
+    pub(crate) fn mul_fp2_wide_by_nonresidue(fe: [FqWide<FqConfig>; 2]) -> [FqWide<FqConfig>; 2] {
+        [fe[1].mul_by_u64(7 * 17), fe[0].mul_by_u64(7)]
+    }
+
+    /// Reduces the coefficients of an unreduced element.
+    #[inline]
+    pub(crate) fn reduce(fe: &[FqWide<FqConfig>; 6]) -> Fq6 {
+        let c = lazy::reduce(fe);
+        Fq6::new(
+            Fq2::new(c[0], c[1]),
+            Fq2::new(c[2], c[3]),
+            Fq2::new(c[4], c[5]),
+        )
+    }
+}
+
+/// Lays out the coefficients of an unreduced element of Fq6 from those of its Fq2 coefficients.
+#[inline]
+fn concat<T: Copy>(c0: [T; 2], c1: [T; 2], c2: [T; 2]) -> [T; 6] {
+    [c0[0], c0[1], c1[0], c1[1], c2[0], c2[1]]
+}
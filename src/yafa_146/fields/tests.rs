@@ -1,8 +1,151 @@
 use crate::yafa_146::*;
 use ark_algebra_test_templates::*;
+use ark_ff::{BigInt, BigInteger, Field, One, PrimeField};
+use ark_std::{test_rng, vec, vec::Vec, UniformRand};
 
-test_field!(fr; Fr; mont_prime_field);
+test_field!(fr; Fr; prime_field);
 test_field!(fq; Fq; mont_prime_field);
 test_field!(fq2; Fq2);
 test_field!(fq6; Fq6);
 test_field!(fq12; Fq12);
+
+// Maps `f` to the cyclotomic subgroup by raising it to the power (q^6 - 1) * (q^2 + 1).
+fn cyclotomic_element(f: Fq12) -> Fq12 {
+    let mut f1 = f;
+    f1.conjugate_in_place();
+    f1 *= &f.inverse().unwrap();
+    let mut f2 = f1;
+    f2.frobenius_map(2);
+    f1 * &f2
+}
+
+#[test]
+fn test_fq12_compressed_square() {
+    let mut rng = test_rng();
+
+    for _ in 0..20 {
+        let mut a = cyclotomic_element(Fq12::rand(&mut rng));
+        let mut g = Fq12Config::cyclotomic_compress(&a);
+        assert_eq!(Fq12Config::cyclotomic_decompress(&g), a);
+
+        for _ in 0..10 {
+            Fq12Config::compressed_square_in_place(&mut g);
+            a.square_in_place();
+        }
+        assert_eq!(Fq12Config::cyclotomic_decompress(&g), a);
+    }
+
+    let g = Fq12Config::cyclotomic_compress(&Fq12::one());
+    assert_eq!(Fq12Config::cyclotomic_decompress(&g), Fq12::one());
+}
+
+// Elements with all coefficients -1, which give the largest unreduced sums, followed by
+// random ones.
+fn lazy_test_elements() -> Vec<Fq12> {
+    let mut rng = test_rng();
+    let fq2 = Fq2::new(-Fq::ONE, -Fq::ONE);
+    let fq6 = Fq6::new(fq2, fq2, fq2);
+    let mut elements = vec![Fq12::ZERO, Fq12::one(), -Fq12::one(), Fq12::new(fq6, fq6)];
+    elements.extend((0..20).map(|_| Fq12::rand(&mut rng)));
+    elements
+}
+
+#[test]
+fn test_fq6_lazy_square() {
+    for a in lazy_test_elements().iter().flat_map(|a| [a.c0, a.c1]) {
+        let mut b = a;
+        Fq6Config::lazy_square_in_place(&mut b);
+        assert_eq!(b, a.square());
+    }
+}
+
+#[test]
+fn test_fq12_lazy_mul_and_square() {
+    let elements = lazy_test_elements();
+    for a in &elements {
+        let mut b = *a;
+        Fq12Config::lazy_square_in_place(&mut b);
+        assert_eq!(b, a.square());
+
+        for c in &elements {
+            let mut b = *a;
+            Fq12Config::lazy_mul_assign(&mut b, c);
+            assert_eq!(b, *a * c);
+        }
+    }
+}
+
+#[test]
+fn test_fq12_lazy_mul_by_034() {
+    let elements = lazy_test_elements();
+    for (a, c) in elements.iter().zip(elements.iter().rev()) {
+        let (c0, c3, c4) = (c.c0.c0, c.c1.c0, c.c1.c1);
+        let mut b = *a;
+        Fq12Config::lazy_mul_by_034(&mut b, &c0, &c3, &c4);
+        let mut expected = *a;
+        expected.mul_by_034(&c0, &c3, &c4);
+        assert_eq!(b, expected);
+    }
+}
+
+#[test]
+fn test_fq_sqrt() {
+    let mut rng = test_rng();
+
+    for _ in 0..20 {
+        let a = Fq::rand(&mut rng);
+        let root = FqConfig::sqrt(&a);
+        assert_eq!(root.is_some(), a.legendre().is_qr());
+        assert_eq!(FqConfig::sqrt_ct(&a), root);
+        if let Some(root) = root {
+            assert_eq!(root.square(), a);
+        }
+
+        let square = a.square();
+        assert_eq!(FqConfig::sqrt(&square).unwrap().square(), square);
+    }
+
+    assert_eq!(FqConfig::sqrt(&Fq::ZERO), Some(Fq::ZERO));
+    assert_eq!(FqConfig::sqrt_ct(&Fq::ZERO), Some(Fq::ZERO));
+}
+
+#[test]
+fn test_fq_inverse() {
+    let mut q_minus_two = Fq::MODULUS;
+    q_minus_two.sub_with_borrow(&BigInt::from(2u64));
+
+    let mut rng = test_rng();
+
+    let mut elements = vec![Fq::one(), -Fq::one(), Fq::from(2u64), -Fq::from(2u64)];
+    elements.extend((0..50).map(|_| Fq::rand(&mut rng)));
+    for a in elements {
+        let a_inv = a.inverse().unwrap();
+        assert_eq!(a_inv, a.pow(q_minus_two));
+        assert!((a * a_inv).is_one());
+    }
+
+    assert_eq!(Fq::ZERO.inverse(), None);
+}
+
+#[test]
+fn test_fq2_sqrt() {
+    let mut rng = test_rng();
+
+    for _ in 0..20 {
+        let a = Fq2::rand(&mut rng);
+        // Every element of Fq is a square in Fq2.
+        let b = Fq2::new(Fq::rand(&mut rng), Fq::ZERO);
+
+        for a in [a, a.square(), b] {
+            let root = Fq2Config::sqrt(&a);
+            assert_eq!(root.is_some(), a.legendre().is_qr());
+            assert_eq!(Fq2Config::sqrt_ct(&a), root);
+            if let Some(root) = root {
+                assert_eq!(root.square(), a);
+            }
+        }
+    }
+
+    assert_eq!(Fq2Config::sqrt(&Fq2::ZERO), Some(Fq2::ZERO));
+    assert_eq!(Fq2Config::sqrt_ct(&Fq2::ZERO), Some(Fq2::ZERO));
+}